@@ -0,0 +1,44 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gdbus;
+extern crate gtk;
+
+use gdbus::bus::DBusDaemon;
+
+fn main() {
+    gtk::init().unwrap();
+
+    let daemon = DBusDaemon::new().unwrap();
+    for name in daemon.list_names().unwrap() {
+        if !name.starts_with(':') {
+            let owner = daemon.get_name_owner(&name).unwrap_or_default();
+            let process_id = daemon.get_connection_unix_process_id(&name).ok();
+            println!("{} owned by {} (process {:?})", name, owner, process_id);
+        }
+    }
+
+    let _name_owner_changed = daemon.connect_name_owner_changed(|name, old_owner, new_owner| {
+        println!("{}: {:?} -> {:?}", name, old_owner, new_owner);
+    });
+
+    gtk::main();
+}
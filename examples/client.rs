@@ -29,6 +29,7 @@ dbus_interface!(
 #[dbus("org.gtk.GDBus.TestInterface")]
 interface TestClass {
     fn decrement_increment(number: i64) -> (i64, u8);
+    fn divide_hundred(divisor: i64) -> i64;
     fn hello_world(greeting: &str) -> String;
     fn increment(number: i64) -> i64;
     fn is_true(boolean: bool) -> bool;
@@ -36,17 +37,24 @@ interface TestClass {
     fn log_default();
     fn multiple_results(number: i64) -> (i16, u16, i32, u32, u64);
     fn print_char(character: char);
+    // The connect method name is given explicitly since it cannot be generated from the signal name.
+    signal greeted(greeting: String), connect_greeted;
 }
 );
 
 fn main() {
     gtk::init().unwrap();
 
-    let test_object = TestClass::new("org.gtk.GDBus.TestServer", "/org/gtk/GDBus/TestObject");
+    let test_object = TestClass::new("org.gtk.GDBus.TestServer", "/org/gtk/GDBus/TestObject").unwrap();
+    let _greeted = test_object.connect_greeted(|greeting| println!("Greeted with: {}", greeting));
     if let Err(error) = test_object.is_true(true) {
         println!("Error: {}", error);
     }
     println!("decrement_increment(41): {:?}", test_object.decrement_increment(41).unwrap());
+    println!("divide_hundred(4): {}", test_object.divide_hundred(4).unwrap());
+    if let Err(error) = test_object.divide_hundred(0) {
+        println!("divide_hundred(0): {}", error);
+    }
     println!("hello_world(\"Me\"): {}", test_object.hello_world("Me").unwrap());
     println!("is_true(true): {}", test_object.is_true(true).unwrap());
     println!("increment(41): {}", test_object.increment(41).unwrap());
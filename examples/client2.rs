@@ -28,8 +28,12 @@ extern crate gtk;
 dbus_interface!(
 #[dbus("org.gtk.GDBus.TestInterface")]
 interface TestClass {
+    // The setter name is given explicitly since it cannot be generated from the property name.
+    property number: i64, set_number;
+
     fn get_number(&self) -> i64;
-    fn get_number_plus_x(&self, x: i64) -> i64;
+    // Also generate an asynchronous version taking a callback, and one returning a future.
+    fn get_number_plus_x(&self, x: i64) -> i64, get_number_plus_x_async, get_number_plus_x_future;
     fn increment(&mut self);
     fn increment_by(&mut self, x: i64);
 }
@@ -38,9 +42,13 @@ interface TestClass {
 fn main() {
     gtk::init().unwrap();
 
-    let test_object = TestClass::new("org.gtk.GDBus.TestServer", "/org/gtk/GDBus/TestObject");
+    let test_object = TestClass::new("org.gtk.GDBus.TestServer", "/org/gtk/GDBus/TestObject").unwrap();
     println!("get_number(): {}", test_object.get_number().unwrap());
+    println!("number: {}", test_object.number().unwrap());
+    test_object.set_number(42).unwrap();
+    println!("get_all().number: {:?}", test_object.get_all().unwrap().number);
     println!("get_number_plus_x(10): {}", test_object.get_number_plus_x(10).unwrap());
+    test_object.get_number_plus_x_async(20, |result| println!("get_number_plus_x_async(20): {}", result.unwrap()));
     test_object.increment().ok();
     println!("get_number(): {}", test_object.get_number().unwrap());
     test_object.increment_by(-1).ok();
@@ -0,0 +1,62 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+#![allow(non_upper_case_globals)]
+
+#[macro_use]
+extern crate gdbus;
+extern crate gio_sys;
+extern crate glib;
+extern crate glib_sys;
+extern crate gtk;
+
+dbus_class!(class Device (name: String, percentage: u8) {
+    #[dbus("org.gtk.GDBus.Device")]
+    interface {
+        property name: String (read)
+
+        fn rename(&mut self, name: String) -> () {
+            self.name = name;
+        }
+    }
+
+    #[dbus("org.gtk.GDBus.Device.Battery")]
+    interface {
+        property percentage: u8 (read)
+
+        fn charge(&mut self) -> u8 {
+            self.percentage = 100;
+            self.percentage
+        }
+    }
+});
+
+fn main() {
+    gtk::init().unwrap();
+
+    let mut devices = Device::new("org.gtk.GDBus.DeviceServer", "Keyboard".to_string(), 40)
+        .object_path("/org/gtk/GDBus/Device/0");
+    devices.add_object("/org/gtk/GDBus/Device/1", "Mouse".to_string(), 75).unwrap();
+    devices.run().unwrap();
+
+    gtk::main();
+}
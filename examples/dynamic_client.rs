@@ -0,0 +1,55 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gdbus;
+extern crate gtk;
+
+use gdbus::own_name::Type;
+use gdbus::proxy::{Proxy, CALL_FLAGS_NONE, PROXY_FLAGS_NONE};
+use gdbus::variant::FromVariant;
+
+fn main() {
+    gtk::init().unwrap();
+
+    let proxy = Proxy::new_for_bus_sync(Type::Session, PROXY_FLAGS_NONE, None, "org.gtk.GDBus.TestServer", "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface").unwrap();
+    println!("Owner: {:?}", proxy.name_owner());
+    let _owner_handler = proxy.connect_name_owner_notify(|_, name_owner| println!("New owner: {:?}", name_owner));
+    let _signal_handler = proxy.connect_signal(|_, _, signal_name, _| println!("Received signal {}", signal_name));
+
+    match proxy.call_sync("increment", (41i64,), CALL_FLAGS_NONE, -1) {
+        Ok(reply) => {
+            let (number,): (i64,) = FromVariant::from_variant(&reply);
+            println!("increment(41): {}", number);
+        },
+        Err(error) => println!("Error: {}", error),
+    }
+    proxy.call("hello_world", ("Me",), CALL_FLAGS_NONE, -1, |result| {
+        match result {
+            Ok(reply) => {
+                let (response,): (String,) = FromVariant::from_variant(&reply);
+                println!("hello_world(\"Me\"): {}", response);
+            },
+            Err(error) => println!("Error: {}", error),
+        }
+    });
+
+    gtk::main();
+}
@@ -0,0 +1,85 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gdbus;
+extern crate glib;
+extern crate gtk;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gdbus::connection::Connection;
+use gdbus::interface::Interface;
+use gdbus::own_name::{OwnName, Type, NAME_OWNER_FLAGS_NONE};
+
+struct Counter {
+    number: i64,
+}
+
+#[gdbus::interface("org.gtk.GDBus.TestInterface")]
+impl Counter {
+    /// The current number, readable and writable by the clients.
+    #[property]
+    fn number(&self) -> i64 {
+        self.number
+    }
+
+    #[property]
+    fn set_number(&mut self, number: i64) {
+        self.number = number;
+    }
+
+    fn get_number(&self) -> i64 {
+        self.number
+    }
+
+    fn get_number_plus_x(&self, x: i64) -> i64 {
+        self.number + x
+    }
+
+    fn increment(&mut self) {
+        self.number += 1;
+    }
+
+    #[annotation("org.freedesktop.DBus.Deprecated" = "true")]
+    fn increment_by(&mut self, x: i64) {
+        self.number += x;
+    }
+}
+
+fn main() {
+    if ::std::env::args().any(|arg| arg == "--introspect") {
+        print!("{}", Counter::interface_info().unwrap().to_xml());
+        return;
+    }
+
+    gtk::init().unwrap();
+
+    let connection = Connection::get_sync(Type::Session).unwrap();
+    let counter = Rc::new(RefCell::new(Counter {
+        number: 42,
+    }));
+    connection.register_interface("/org/gtk/GDBus/TestObject", &counter).unwrap();
+    let _own_name = OwnName::new_on_connection(&connection, "org.gtk.GDBus.TestServer", NAME_OWNER_FLAGS_NONE)
+        .build();
+
+    gtk::main();
+}
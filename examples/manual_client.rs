@@ -31,7 +31,7 @@ use gdbus::watch::{Watch, NAME_WATCHER_FLAGS_NONE};
 fn on_name_appeared(connection: &Connection, name_owner: &str) {
     let method_call_message = Message::new_method_call(name_owner, "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface", "decrement_increment");
     method_call_message.set_body((41i64,));
-    match connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE) {
+    match connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE, 100) {
         Ok(message) => {
             let (decrement, increment): (i64, u8) = FromVariant::from_variant(&message.get_body());
             println!("Response: ({}, {})", decrement, increment);
@@ -45,6 +45,7 @@ fn main() {
 
     let _watcher = Watch::name(Type::Session, "org.gtk.GDBus.TestServer", NAME_WATCHER_FLAGS_NONE)
         .connect_name_appeared(on_name_appeared)
+        .connect_name_vanished(|_| println!("Name vanished"))
         .build();
 
     gtk::main();
@@ -33,7 +33,7 @@ const NAME_OWNER: &'static str = "org.gtk.GDBus.TestServer";
 fn on_name_appeared(connection: &Connection, _name_owner: &str) {
     let method_call_message = Message::new_method_call(NAME_OWNER, "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface", "hello_world");
     method_call_message.set_body(("My Name",));
-    let method_reply_message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE);
+    let method_reply_message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE, 100);
     match method_reply_message {
         Ok(message) => {
             let (response,): (String,) = FromVariant::from_variant(&message.get_body());
@@ -47,25 +47,25 @@ fn on_name_appeared(connection: &Connection, _name_owner: &str) {
     let num: i64 = 41;
     let method_call_message = Message::new_method_call(NAME_OWNER, "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface", "increment");
     method_call_message.set_body((num,));
-    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE).unwrap();
+    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE, 100).unwrap();
     let (response,): (i64,) = FromVariant::from_variant(&message.get_body());
     println!("Response: {}", response);
 
     let method_call_message = Message::new_method_call(NAME_OWNER, "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface", "decrement_increment");
     method_call_message.set_body((num,));
-    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE).unwrap();
+    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE, 100).unwrap();
     let (decrement, increment): (i64, u8) = FromVariant::from_variant(&message.get_body());
     println!("Response: ({}, {})", decrement, increment);
 
     let method_call_message = Message::new_method_call(NAME_OWNER, "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface", "multiple_results");
     method_call_message.set_body((num,));
-    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE).unwrap();
+    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE, 100).unwrap();
     let result: (i16, u16, i32, u32, u64) = FromVariant::from_variant(&message.get_body());
     println!("Response: {:?}", result);
 
     let method_call_message = Message::new_method_call(NAME_OWNER, "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface", "is_true");
     method_call_message.set_body((true,));
-    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE).unwrap();
+    let message = connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE, 100).unwrap();
     let (response,): (bool,) = FromVariant::from_variant(&message.get_body());
     println!("Response: {}", response);
 
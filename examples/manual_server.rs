@@ -32,7 +32,7 @@ use gdbus::node_info::NodeInfo;
 use gdbus::own_name::{OwnName, Type, NAME_OWNER_FLAGS_NONE};
 use gdbus::variant::{FromVariant, Variant};
 
-fn handle_method_call(method_name: &str, args: Variant, invocation: &MethodInvocation) {
+fn handle_method_call(method_name: &str, args: Variant, invocation: MethodInvocation) {
     match method_name {
         "decrement_increment" => {
             let (number,): (i64,) = FromVariant::from_variant(&args);
@@ -40,7 +40,7 @@ fn handle_method_call(method_name: &str, args: Variant, invocation: &MethodInvoc
             let increment = number as u8 + 1;
             invocation.return_value((decrement, increment));
         },
-        _ => unreachable!(),
+        _ => invocation.return_dbus_error("org.freedesktop.DBus.Error.UnknownMethod", &format!("Unknown method {}", method_name)),
     }
 }
 
@@ -100,7 +100,7 @@ const NODE: ::gio_sys::GDBusNodeInfo = ::gio_sys::GDBusNodeInfo {
 
 fn on_bus_acquired(connection: &Connection) {
     let introspection_data = NodeInfo::new(&mut NODE);
-    connection.register_object("/org/gtk/GDBus/TestObject", introspection_data.interface(0), handle_method_call)
+    connection.register_object("/org/gtk/GDBus/TestObject", introspection_data.interface(0), handle_method_call).unwrap();
 }
 
 fn main() {
@@ -108,6 +108,11 @@ fn main() {
 
     let _own_name = OwnName::new(Type::Session, "org.gtk.GDBus.TestServer", NAME_OWNER_FLAGS_NONE)
         .connect_bus_acquired(on_bus_acquired)
+        .connect_name_acquired(|_| println!("Name acquired"))
+        .connect_name_lost(|_| {
+            println!("Name lost");
+            gtk::main_quit();
+        })
         .build();
 
     gtk::main();
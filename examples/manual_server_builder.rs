@@ -0,0 +1,77 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+extern crate gdbus;
+extern crate gtk;
+
+use gdbus::connection::Connection;
+use gdbus::method_invocation::MethodInvocation;
+use gdbus::node_info::{InterfaceInfo, MethodInfo, PropertyInfo, SignalInfo, PROPERTY_INFO_FLAGS_READABLE};
+use gdbus::own_name::{OwnName, Type, NAME_OWNER_FLAGS_NONE};
+use gdbus::variant::{FromVariant, ToVariant, Variant};
+
+fn handle_method_call(method_name: &str, args: Variant, invocation: MethodInvocation) {
+    match method_name {
+        "decrement_increment" => {
+            let (number,): (i64,) = FromVariant::from_variant(&args);
+            invocation.return_value((number - 1, number as u8 + 1));
+        },
+        "hello_world" => {
+            let (greeting,): (String,) = FromVariant::from_variant(&args);
+            let connection = invocation.connection();
+            if let Err(error) = connection.emit_signal(None, "/org/gtk/GDBus/TestObject", "org.gtk.GDBus.TestInterface", "greeted", (greeting.as_str(),).to_variant()) {
+                println!("{}", error);
+            }
+            invocation.return_value((format!("You greeted me with '{}'. Thanks!", greeting),));
+        },
+        _ => invocation.return_dbus_error("org.freedesktop.DBus.Error.UnknownMethod", &format!("Unknown method {}", method_name)),
+    }
+}
+
+fn on_bus_acquired(connection: &Connection) {
+    let interface = InterfaceInfo::builder("org.gtk.GDBus.TestInterface")
+        .method(MethodInfo::builder("decrement_increment")
+            .in_arg("number", "x")
+            .out_arg("decrement", "x")
+            .out_arg("increment", "y"))
+        .method(MethodInfo::builder("hello_world")
+            .in_arg("greeting", "s")
+            .out_arg("response", "s")
+            .annotation("org.freedesktop.DBus.Deprecated", "true"))
+        .signal(SignalInfo::builder("greeted")
+            .arg("greeting", "s"))
+        .property(PropertyInfo::builder("version", "s", PROPERTY_INFO_FLAGS_READABLE))
+        .build();
+    let result = interface.and_then(|interface| connection.register_object("/org/gtk/GDBus/TestObject", interface, handle_method_call));
+    if let Err(error) = result {
+        println!("{}", error);
+    }
+}
+
+fn main() {
+    gtk::init().unwrap();
+
+    let _own_name = OwnName::new(Type::Session, "org.gtk.GDBus.TestServer", NAME_OWNER_FLAGS_NONE)
+        .connect_bus_acquired(on_bus_acquired)
+        .build();
+
+    gtk::main();
+}
@@ -65,7 +65,7 @@ fn hello_world(greeting: &str) -> String {
     format!("You greeted me with '{}'. Thanks!", greeting)
 }
 
-fn handle_method_call(method_name: &str, args: Variant, invocation: &MethodInvocation) {
+fn handle_method_call(method_name: &str, args: Variant, invocation: MethodInvocation) {
     match method_name {
         "decrement_increment" => {
             let (number,): (i64,) = FromVariant::from_variant(&args);
@@ -89,20 +89,26 @@ fn handle_method_call(method_name: &str, args: Variant, invocation: &MethodInvoc
         },
         "log" => {
             let (message,): (String,) = FromVariant::from_variant(&args);
-            println!("LOG: {}", message);
+            let sender = invocation.sender().unwrap_or_else(|| "unknown".to_string());
+            println!("LOG from {}: {}", sender, message);
+            invocation.return_value(());
         },
         "multiple_results" => {
             let (number,): (i64,) = FromVariant::from_variant(&args);
             invocation.return_value((number as i16 - 2, number as u16 - 1, number as i32, number as u32 + 1, number as u64 + 2));
         },
-        _ => unreachable!(),
+        _ => invocation.return_dbus_error("org.freedesktop.DBus.Error.UnknownMethod", &format!("Unknown method {}", method_name)),
     }
 }
 
 fn on_bus_acquired(connection: &Connection) {
     match NodeInfo::new_for_xml(INTROSPECTION_XML) {
         Ok(introspection_data) => {
-            connection.register_object("/org/gtk/GDBus/TestObject", introspection_data.interface(0), handle_method_call)
+            if let Some(interface) = introspection_data.lookup_interface("org.gtk.GDBus.TestInterface") {
+                if let Err(error) = connection.register_object("/org/gtk/GDBus/TestObject", interface, handle_method_call) {
+                    println!("{}", error);
+                }
+            }
         },
         Err(error) => println!("{}", error),
     }
@@ -0,0 +1,59 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#[macro_use]
+extern crate gdbus;
+extern crate gio_sys;
+extern crate glib;
+extern crate gtk;
+
+use gdbus::connection::Connection;
+use gdbus::object_manager::ObjectManagerClient;
+use gdbus::own_name::Type;
+
+dbus_interface!(
+#[dbus("org.gtk.GDBus.Device")]
+interface Device {
+    fn rename(name: &str);
+    property name: String;
+}
+);
+
+fn main() {
+    gtk::init().unwrap();
+
+    let connection = Connection::get_sync(Type::Session).unwrap();
+    let client = ObjectManagerClient::new(&connection, "org.gtk.GDBus.DeviceServer", "/org/gtk/GDBus/Devices")
+        .connect_object_added(|object_path, interfaces| {
+            let mut interface_names: Vec<_> = interfaces.keys().collect();
+            interface_names.sort();
+            println!("Object added: {} {:?}", object_path, interface_names);
+        })
+        .connect_object_removed(|object_path| println!("Object removed: {}", object_path))
+        .build()
+        .unwrap();
+
+    for (object_path, device) in client.proxies::<Device>() {
+        println!("{}: {}", object_path, device.name().unwrap());
+    }
+
+    gtk::main();
+}
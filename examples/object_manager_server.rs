@@ -0,0 +1,82 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+#![allow(non_upper_case_globals)]
+
+#[macro_use]
+extern crate gdbus;
+extern crate gio_sys;
+extern crate glib;
+extern crate glib_sys;
+extern crate gtk;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gdbus::connection::Connection;
+use gdbus::object_manager::ObjectManagerServer;
+use gdbus::own_name::{OwnName, Type, NAME_OWNER_FLAGS_NONE};
+
+dbus_class!(class Device (name: String) {
+    #[dbus("org.gtk.GDBus.Device")]
+    interface {
+        property name: String (read)
+
+        fn rename(&mut self, name: String) -> () {
+            self.name = name;
+        }
+    }
+});
+
+struct Counter {
+    number: i64,
+}
+
+#[gdbus::interface("org.gtk.GDBus.Counter")]
+impl Counter {
+    #[property]
+    fn number(&self) -> i64 {
+        self.number
+    }
+
+    fn increment(&mut self) {
+        self.number += 1;
+    }
+}
+
+fn main() {
+    gtk::init().unwrap();
+
+    let connection = Connection::get_sync(Type::Session).unwrap();
+    let manager = ObjectManagerServer::new(&connection, "/org/gtk/GDBus/Devices").unwrap();
+    manager.export("/org/gtk/GDBus/Devices/Keyboard", Device::new("org.gtk.GDBus.Keyboard", "Keyboard".to_string())).unwrap();
+    manager.export("/org/gtk/GDBus/Devices/Counter", Rc::new(RefCell::new(Counter { number: 0 }))).unwrap();
+    let _own_name = OwnName::new_on_connection(&connection, "org.gtk.GDBus.DeviceServer", NAME_OWNER_FLAGS_NONE)
+        .build();
+
+    // Unplug the keyboard after 10 seconds to emit InterfacesRemoved.
+    glib::timeout_add(10000, move || {
+        manager.unexport("/org/gtk/GDBus/Devices/Keyboard");
+        glib::Continue(false)
+    });
+
+    gtk::main();
+}
@@ -24,14 +24,38 @@
 #[macro_use]
 extern crate gdbus;
 extern crate gio_sys;
+extern crate glib;
 extern crate glib_sys;
 extern crate gtk;
 
+use gdbus::method_invocation::DBusError;
+
+struct DivisionByZero;
+
+impl DBusError for DivisionByZero {
+    fn name(&self) -> String {
+        "org.gtk.GDBus.TestInterface.Error.DivisionByZero".to_string()
+    }
+
+    fn message(&self) -> String {
+        "Cannot divide by zero".to_string()
+    }
+}
+
 dbus_class!("org.gtk.GDBus.TestInterface", class TestClass {
     fn decrement_increment(number: i64) -> (i64, u8) {
         (number - 1, number as u8 + 1)
     }
 
+    fn divide_hundred(divisor: i64) -> Result<i64, DivisionByZero> {
+        if divisor == 0 {
+            Err(DivisionByZero)
+        }
+        else {
+            Ok(100 / divisor)
+        }
+    }
+
     fn hello_world(greeting: &str) -> String {
         format!("You greeted me with '{}'. Thanks!", greeting)
     }
@@ -44,10 +68,13 @@ dbus_class!("org.gtk.GDBus.TestInterface", class TestClass {
         boolean
     }
 
+    #[annotation("org.freedesktop.DBus.Method.NoReply" = "true")]
     fn log(message: &str) -> () {
         println!("LOG: {}", message);
     }
 
+    #[annotation("org.freedesktop.DBus.Deprecated" = "true")]
+    #[annotation("org.freedesktop.DBus.Method.NoReply" = "true")]
     fn log_default() -> () {
         println!("LOG: Default");
     }
@@ -62,10 +89,16 @@ dbus_class!("org.gtk.GDBus.TestInterface", class TestClass {
 });
 
 fn main() {
+    if ::std::env::args().any(|arg| arg == "--introspect") {
+        print!("{}", TestClass::introspection_xml());
+        return;
+    }
+
     gtk::init().unwrap();
 
-    let mut test_object = TestClass::new("org.gtk.GDBus.TestServer");
-    test_object.run("/org/gtk/GDBus/TestObject");
+    let mut test_object = TestClass::new("org.gtk.GDBus.TestServer")
+        .object_path("/org/gtk/GDBus/TestObject");
+    test_object.run().unwrap();
 
     gtk::main();
 }
@@ -24,10 +24,16 @@
 #[macro_use]
 extern crate gdbus;
 extern crate gio_sys;
+extern crate glib;
 extern crate glib_sys;
 extern crate gtk;
 
+use gdbus::future::{self, Reply};
+use glib::Continue;
+
 dbus_class!("org.gtk.GDBus.TestInterface", class TestClass (number: i64) {
+    property number: i64 (readwrite)
+
     fn get_number(&self) -> i64 {
         self.number
     }
@@ -36,6 +42,17 @@ dbus_class!("org.gtk.GDBus.TestInterface", class TestClass (number: i64) {
         self.number + x
     }
 
+    // The reply is sent when the future completes, without blocking the main loop.
+    fn get_number_later(&self) -> Reply<i64> {
+        let (sender, reply) = future::channel();
+        let number = self.number;
+        glib::timeout_add(1000, move || {
+            sender.send(Ok(number));
+            Continue(false)
+        });
+        reply
+    }
+
     fn increment(&mut self) -> () {
         self.number = self.get_number() + 1;
     }
@@ -48,8 +65,9 @@ dbus_class!("org.gtk.GDBus.TestInterface", class TestClass (number: i64) {
 fn main() {
     gtk::init().unwrap();
 
-    let mut test_object = TestClass::new("org.gtk.GDBus.TestServer", 42);
-    test_object.run("/org/gtk/GDBus/TestObject");
+    let mut test_object = TestClass::new("org.gtk.GDBus.TestServer", 42)
+        .object_path("/org/gtk/GDBus/TestObject");
+    test_object.run().unwrap();
 
     gtk::main();
 }
@@ -0,0 +1,41 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+
+extern crate gdbus;
+extern crate gtk;
+
+use gdbus::connection::Connection;
+use gdbus::own_name::Type;
+use gdbus::watch::{Watch, NAME_WATCHER_FLAGS_NONE};
+
+fn main() {
+    gtk::init().unwrap();
+
+    // Watch the name on an existing connection instead of letting the watcher connect to the bus.
+    let connection = Connection::get_sync(Type::Session).unwrap();
+    let _watcher = Watch::name_on_connection(&connection, "org.gtk.GDBus.TestServer", NAME_WATCHER_FLAGS_NONE)
+        .connect_name_appeared(|_, name_owner| println!("Name appeared, owned by {}", name_owner))
+        .connect_name_vanished(|_| println!("Name vanished"))
+        .build();
+
+    gtk::main();
+}
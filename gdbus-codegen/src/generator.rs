@@ -0,0 +1,590 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Generation of the Rust code for the parsed interfaces.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use introspection::{Access, Arg, Interface, Method, Property, Signal};
+
+const KEYWORDS: &[&str] = &["abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "typeof", "union", "unsafe", "unsized", "use", "virtual", "where", "while", "yield"];
+
+/// The maximum number of values in a tuple converted from or to a `Variant`.
+const MAX_TUPLE_SIZE: usize = 5;
+
+/// Position of a value, which decides how strings are passed.
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    Argument,
+    Value,
+}
+
+pub fn generate(interfaces: &[Interface]) -> String {
+    let mut code = "// This file was generated by gdbus-codegen. Do not edit.\n".to_string();
+    for signature in variant_signatures(interfaces) {
+        code.push_str("\ngdbus::dbus_variant_type! {\n");
+        writeln!(code, "    /// A value of the D-Bus type `{}`.", signature).unwrap();
+        writeln!(code, "    pub struct {}({:?});", variant_type_name(signature), signature).unwrap();
+        code.push_str("}\n");
+    }
+    let mut last_elements = HashSet::new();
+    let duplicates: HashSet<_> = interfaces.iter()
+        .map(|interface| interface.name.rsplit('.').next().unwrap_or(""))
+        .filter(|&last_element| !last_elements.insert(last_element))
+        .collect();
+    for interface in interfaces {
+        let last_element = interface.name.rsplit('.').next().unwrap_or("");
+        // Use the full interface name when two interfaces end with the same element.
+        let type_name =
+            if duplicates.contains(last_element) {
+                interface.name.split('.').map(camel_case).collect()
+            }
+            else {
+                camel_case(last_element)
+            };
+        generate_proxy(&mut code, interface, &type_name);
+        generate_server(&mut code, interface, &type_name);
+    }
+    code
+}
+
+fn generate_proxy(code: &mut String, interface: &Interface, type_name: &str) {
+    writeln!(code, "\n/// Proxy for the `{}` interface.", interface.name).unwrap();
+    writeln!(code, "#[gdbus::proxy({:?})]", interface.name).unwrap();
+    writeln!(code, "pub trait {}Proxy {{", type_name).unwrap();
+    for method in &interface.methods {
+        match method_signature(method, "&self") {
+            Ok(signature) => {
+                dbus_name(code, &method.name);
+                writeln!(code, "    {};", signature).unwrap();
+            },
+            Err(signature) => skipped(code, "method", &method.name, &signature),
+        }
+    }
+    for property in &interface.properties {
+        dbus_name(code, &property.name);
+        match property.access {
+            Access::Read => writeln!(code, "    #[property(read)]").unwrap(),
+            Access::ReadWrite => writeln!(code, "    #[property]").unwrap(),
+            Access::Write => writeln!(code, "    #[property(write)]").unwrap(),
+        }
+        writeln!(code, "    fn {}(&self) -> {};", identifier(&snake_case(&property.name)), rust_type(&property.signature, Position::Value)).unwrap();
+    }
+    for signal in &interface.signals {
+        match signal_args(signal) {
+            Ok(args) => {
+                dbus_name(code, &signal.name);
+                writeln!(code, "    #[signal]").unwrap();
+                writeln!(code, "    fn {}(&self{});", identifier(&snake_case(&signal.name)), args).unwrap();
+            },
+            Err(signature) => skipped(code, "signal", &signal.name, &signature),
+        }
+    }
+    code.push_str("}\n");
+}
+
+fn generate_server(code: &mut String, interface: &Interface, type_name: &str) {
+    let methods: Vec<_> = interface.methods.iter()
+        .filter_map(|method| method_signature(method, "&mut self").ok().map(|signature| (method, signature)))
+        .collect();
+    let properties: Vec<_> = interface.properties.iter()
+        .map(|property| (property, rust_type(&property.signature, Position::Value)))
+        .collect();
+
+    writeln!(code, "\n/// Implementation of the `{}` interface.", interface.name).unwrap();
+    writeln!(code, "/// Wrap it in a `{}Server` to register it with `Connection::register_interface()`.", type_name).unwrap();
+    writeln!(code, "pub trait {} {{", type_name).unwrap();
+    for method in &interface.methods {
+        match method_signature(method, "&mut self") {
+            Ok(signature) => writeln!(code, "    {};", signature).unwrap(),
+            Err(signature) => skipped(code, "method", &method.name, &signature),
+        }
+    }
+    for &(property, ref property_type) in &properties {
+        let name = snake_case(&property.name);
+        if property.access != Access::Write {
+            writeln!(code, "    fn {}(&self) -> {};", identifier(&name), property_type).unwrap();
+        }
+        if property.access != Access::Read {
+            writeln!(code, "    fn set_{}(&mut self, value: {});", name, property_type).unwrap();
+        }
+    }
+    code.push_str("}\n");
+
+    writeln!(code, "\n/// Exports a `{}` implementation on the bus.", type_name).unwrap();
+    writeln!(code, "pub struct {}Server<T>(pub T);", type_name).unwrap();
+    writeln!(code, "\n#[gdbus::interface({:?})]", interface.name).unwrap();
+    annotations(code, "", &interface.annotations);
+    writeln!(code, "impl<T: {} + 'static> {}Server<T> {{", type_name, type_name).unwrap();
+    let mut first = true;
+    for &(method, ref signature) in &methods {
+        if !first {
+            code.push('\n');
+        }
+        first = false;
+        dbus_name(code, &method.name);
+        annotations(code, "    ", &method.annotations);
+        let arg_names: Vec<_> = arg_names(&method.in_args);
+        writeln!(code, "    {} {{", signature).unwrap();
+        writeln!(code, "        self.0.{}({})", identifier(&snake_case(&method.name)), arg_names.join(", ")).unwrap();
+        code.push_str("    }\n");
+    }
+    for &(property, ref property_type) in &properties {
+        let name = snake_case(&property.name);
+        if property.access != Access::Write {
+            if !first {
+                code.push('\n');
+            }
+            first = false;
+            property_attributes(code, property);
+            writeln!(code, "    fn {}(&self) -> {} {{", identifier(&name), property_type).unwrap();
+            writeln!(code, "        self.0.{}()", identifier(&name)).unwrap();
+            code.push_str("    }\n");
+        }
+        if property.access != Access::Read {
+            if !first {
+                code.push('\n');
+            }
+            first = false;
+            property_attributes(code, property);
+            writeln!(code, "    fn set_{}(&mut self, value: {}) {{", name, property_type).unwrap();
+            writeln!(code, "        self.0.set_{}(value)", name).unwrap();
+            code.push_str("    }\n");
+        }
+    }
+    code.push_str("}\n");
+}
+
+fn annotations(code: &mut String, indentation: &str, annotations: &[(String, String)]) {
+    for (name, value) in annotations {
+        writeln!(code, "{}#[annotation({:?} = {:?})]", indentation, name, value).unwrap();
+    }
+}
+
+fn property_attributes(code: &mut String, property: &Property) {
+    writeln!(code, "    #[dbus_name({:?})]", property.name).unwrap();
+    annotations(code, "    ", &property.annotations);
+    writeln!(code, "    #[property]").unwrap();
+}
+
+/// Add the `#[dbus_name]` attribute when the Rust name differs from the D-Bus name.
+fn dbus_name(code: &mut String, name: &str) {
+    if snake_case(name) != name {
+        writeln!(code, "    #[dbus_name({:?})]", name).unwrap();
+    }
+}
+
+fn skipped(code: &mut String, kind: &str, name: &str, signature: &str) {
+    writeln!(code, "    // Skipped {} `{}`: unsupported signature `{}`.", kind, name, signature).unwrap();
+}
+
+fn arg_names(args: &[Arg]) -> Vec<String> {
+    args.iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            match arg.name {
+                Some(ref name) => identifier(&snake_case(name)),
+                None => format!("arg{}", index),
+            }
+        })
+        .collect()
+}
+
+/// Get the Rust signature of a method, or the D-Bus signature which cannot be converted.
+fn method_signature(method: &Method, receiver: &str) -> Result<String, String> {
+    if method.in_args.len() > MAX_TUPLE_SIZE || method.out_args.len() > MAX_TUPLE_SIZE {
+        return Err(method.in_args.iter().chain(&method.out_args).map(|arg| arg.signature.as_str()).collect());
+    }
+    let mut signature = format!("fn {}({}", identifier(&snake_case(&method.name)), receiver);
+    for (arg, name) in method.in_args.iter().zip(arg_names(&method.in_args)) {
+        write!(signature, ", {}: {}", name, rust_type(&arg.signature, Position::Argument)).unwrap();
+    }
+    signature.push(')');
+    let out_types: Vec<_> = method.out_args.iter()
+        .map(|arg| rust_type(&arg.signature, Position::Value))
+        .collect();
+    match out_types.len() {
+        0 => (),
+        1 => write!(signature, " -> {}", out_types[0]).unwrap(),
+        _ => write!(signature, " -> ({})", out_types.join(", ")).unwrap(),
+    }
+    Ok(signature)
+}
+
+/// Get the arguments of a signal, or the D-Bus signature which cannot be converted.
+fn signal_args(signal: &Signal) -> Result<String, String> {
+    if signal.args.len() > MAX_TUPLE_SIZE {
+        return Err(signal.args.iter().map(|arg| arg.signature.as_str()).collect());
+    }
+    let mut args = String::new();
+    for (arg, name) in signal.args.iter().zip(arg_names(&signal.args)) {
+        write!(args, ", {}: {}", name, rust_type(&arg.signature, Position::Value)).unwrap();
+    }
+    Ok(args)
+}
+
+/// Get the signatures, in order of appearance, which need a type defined with `dbus_variant_type!`.
+fn variant_signatures(interfaces: &[Interface]) -> Vec<&str> {
+    let mut signatures = vec![];
+    for interface in interfaces {
+        let args = interface.methods.iter()
+            .flat_map(|method| method.in_args.iter().chain(&method.out_args))
+            .chain(interface.signals.iter().flat_map(|signal| &signal.args));
+        let values = args.map(|arg| &arg.signature)
+            .chain(interface.properties.iter().map(|property| &property.signature));
+        for signature in values {
+            if known_type(signature, Position::Value).is_none() && !signatures.contains(&signature.as_str()) {
+                signatures.push(signature);
+            }
+        }
+    }
+    signatures
+}
+
+/// Get the Rust type for a D-Bus signature.
+/// Values which `gdbus` cannot convert to a Rust type are wrapped in a type defined with `dbus_variant_type!`, so that they keep their D-Bus type.
+fn rust_type(signature: &str, position: Position) -> String {
+    known_type(signature, position).unwrap_or_else(|| variant_type_name(signature))
+}
+
+fn known_type(signature: &str, position: Position) -> Option<String> {
+    if let Some(element_type) = signature.strip_prefix('a').and_then(|element| basic_type(element, Position::Value)) {
+        return Some(format!("Vec<{}>", element_type));
+    }
+    basic_type(signature, position).map(str::to_string)
+}
+
+fn basic_type(signature: &str, position: Position) -> Option<&'static str> {
+    let rust_type =
+        match signature {
+            "b" => "bool",
+            "y" => "u8",
+            "n" => "i16",
+            "q" => "u16",
+            "i" => "i32",
+            "u" => "u32",
+            "x" => "i64",
+            "t" => "u64",
+            "d" => "f64",
+            "s" if position == Position::Argument => "&str",
+            "s" => "String",
+            "o" => "::gdbus::variant::ObjectPath",
+            "g" => "::gdbus::variant::Signature",
+            "v" => "::gdbus::variant::Variant",
+            _ => return None,
+        };
+    Some(rust_type)
+}
+
+/// Get the name of the type defined for a D-Bus signature, e.g. `DictStringVariant` for `a{sv}` or `Tuple2I32ArrayString` for `(ias)`.
+fn variant_type_name(signature: &str) -> String {
+    split_type(signature).0
+}
+
+/// Split the first complete type of `signature`, returning its name and the rest of the signature.
+fn split_type(signature: &str) -> (String, &str) {
+    let mut characters = signature.chars();
+    let first = characters.next();
+    let rest = characters.as_str();
+    match first {
+        Some('a') if rest.starts_with('{') => {
+            let (key, rest) = split_type(&rest[1..]);
+            let (value, rest) = split_type(rest);
+            (format!("Dict{}{}", key, value), rest.strip_prefix('}').unwrap_or(rest))
+        },
+        Some('a') => {
+            let (element, rest) = split_type(rest);
+            (format!("Array{}", element), rest)
+        },
+        Some('(') => {
+            let mut fields = vec![];
+            let mut rest = rest;
+            while !rest.is_empty() && !rest.starts_with(')') {
+                let (field, next) = split_type(rest);
+                fields.push(field);
+                rest = next;
+            }
+            // The number of fields keeps the names of nested structures unambiguous.
+            (format!("Tuple{}{}", fields.len(), fields.concat()), rest.strip_prefix(')').unwrap_or(rest))
+        },
+        Some(character) => (basic_type_name(character).to_string(), rest),
+        None => (String::new(), rest),
+    }
+}
+
+fn basic_type_name(character: char) -> &'static str {
+    match character {
+        'b' => "Bool",
+        'y' => "U8",
+        'n' => "I16",
+        'q' => "U16",
+        'i' => "I32",
+        'u' => "U32",
+        'x' => "I64",
+        't' => "U64",
+        'd' => "F64",
+        'h' => "Fd",
+        's' => "String",
+        'o' => "ObjectPath",
+        'g' => "Signature",
+        'v' => "Variant",
+        _ => "Value",
+    }
+}
+
+/// Convert a D-Bus name, like `GetNameOwner` or `getUUID`, to snake case.
+fn snake_case(name: &str) -> String {
+    let characters: Vec<char> = name.chars().collect();
+    let mut result = String::new();
+    for (index, &character) in characters.iter().enumerate() {
+        if character.is_uppercase() && index > 0 {
+            let previous = characters[index - 1];
+            let next_is_lowercase = characters.get(index + 1).map(|next| next.is_lowercase()).unwrap_or(false);
+            if previous != '_' && (previous.is_lowercase() || previous.is_numeric() || (previous.is_uppercase() && next_is_lowercase)) {
+                result.push('_');
+            }
+        }
+        result.extend(character.to_lowercase());
+    }
+    result
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .flat_map(|word| {
+            let mut characters = word.chars();
+            characters.next().into_iter().flat_map(char::to_uppercase).chain(characters)
+        })
+        .collect()
+}
+
+/// Make `name` usable as a Rust identifier.
+fn identifier(name: &str) -> String {
+    match name {
+        "self" | "Self" | "super" | "crate" => format!("{}_", name),
+        _ if KEYWORDS.contains(&name) => format!("r#{}", name),
+        _ if name.starts_with(|character: char| character.is_numeric()) => format!("_{}", name),
+        _ => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use introspection::parse;
+
+    use super::{Position, generate, identifier, rust_type, snake_case};
+
+    #[test]
+    fn snake_case_acronyms() {
+        assert_eq!(snake_case("GetUUID"), "get_uuid");
+        assert_eq!(snake_case("getUUID"), "get_uuid");
+        assert_eq!(snake_case("UUIDString"), "uuid_string");
+        assert_eq!(snake_case("GetNameOwner"), "get_name_owner");
+        assert_eq!(snake_case("already_snake"), "already_snake");
+    }
+
+    #[test]
+    fn identifier_keywords() {
+        assert_eq!(identifier("type"), "r#type");
+        assert_eq!(identifier("move"), "r#move");
+        assert_eq!(identifier("self"), "self_");
+        assert_eq!(identifier("Self"), "Self_");
+        assert_eq!(identifier("super"), "super_");
+        assert_eq!(identifier("3d"), "_3d");
+        assert_eq!(identifier("name"), "name");
+    }
+
+    #[test]
+    fn rust_types() {
+        assert_eq!(rust_type("s", Position::Argument), "&str");
+        assert_eq!(rust_type("s", Position::Value), "String");
+        assert_eq!(rust_type("o", Position::Argument), "::gdbus::variant::ObjectPath");
+        assert_eq!(rust_type("g", Position::Value), "::gdbus::variant::Signature");
+        assert_eq!(rust_type("as", Position::Argument), "Vec<String>");
+        assert_eq!(rust_type("ao", Position::Value), "Vec<::gdbus::variant::ObjectPath>");
+        assert_eq!(rust_type("ay", Position::Value), "Vec<u8>");
+        assert_eq!(rust_type("aas", Position::Value), "ArrayArrayString");
+        assert_eq!(rust_type("a{sv}", Position::Value), "DictStringVariant");
+        assert_eq!(rust_type("(ii)", Position::Argument), "Tuple2I32I32");
+        assert_eq!(rust_type("a{oa{sa{sv}}}", Position::Value), "DictObjectPathDictStringDictStringVariant");
+        assert_eq!(rust_type("((i)i)", Position::Value), "Tuple2Tuple1I32I32");
+        assert_eq!(rust_type("((ii))", Position::Value), "Tuple1Tuple2I32I32");
+    }
+
+    /// Get the signature of a generated Rust type from its `ToFormat` implementation in `gdbus`.
+    fn type_signature(rust_type: &str, code: &str) -> String {
+        if let Some(element_type) = rust_type.strip_prefix("Vec<").and_then(|rust_type| rust_type.strip_suffix('>')) {
+            return format!("a{}", type_signature(element_type, code));
+        }
+        let signature =
+            match rust_type {
+                "bool" => "b",
+                "u8" => "y",
+                "i16" => "n",
+                "u16" => "q",
+                "i32" => "i",
+                "u32" => "u",
+                "i64" => "x",
+                "u64" => "t",
+                "f64" => "d",
+                "&str" | "String" => "s",
+                "::gdbus::variant::ObjectPath" => "o",
+                "::gdbus::variant::Signature" => "g",
+                "::gdbus::variant::Variant" => "v",
+                _ => {
+                    let declaration = format!("    pub struct {}(\"", rust_type);
+                    let start = code.find(&declaration).unwrap_or_else(|| panic!("{} is not defined", rust_type)) + declaration.len();
+                    let end = code[start..].find("\");").unwrap();
+                    &code[start..start + end]
+                },
+            };
+        signature.to_string()
+    }
+
+    #[test]
+    fn signatures_match_xml() {
+        let interfaces = parse(r#"
+            <node>
+              <interface name="org.example.Manager">
+                <method name="GetObjects">
+                  <arg name="root" type="o" direction="in"/>
+                  <arg name="filter" type="a{sv}" direction="in"/>
+                  <arg type="a{oa{sa{sv}}}" direction="out"/>
+                  <arg type="ao" direction="out"/>
+                </method>
+                <method name="Check"><arg name="signature" type="g" direction="in"/><arg name="size" type="(ii)" direction="in"/><arg type="aas" direction="out"/></method>
+                <method name="Send"><arg name="name" type="s" direction="in"/><arg name="data" type="ay" direction="in"/><arg name="value" type="v" direction="in"/></method>
+                <property name="Metadata" type="a{sv}" access="read"/>
+                <property name="Paths" type="ao" access="readwrite"/>
+                <property name="Types" type="ag" access="read"/>
+                <signal name="Moved"><arg name="path" type="o"/><arg name="position" type="(dd)"/></signal>
+              </interface>
+            </node>"#).unwrap();
+        let code = generate(&interfaces);
+        let check = |signature: &str, position: Position| assert_eq!(type_signature(&rust_type(signature, position), &code), signature);
+        for interface in &interfaces {
+            for method in &interface.methods {
+                for arg in &method.in_args {
+                    check(&arg.signature, Position::Argument);
+                }
+                for arg in &method.out_args {
+                    check(&arg.signature, Position::Value);
+                }
+            }
+            for property in &interface.properties {
+                check(&property.signature, Position::Value);
+            }
+            for signal in &interface.signals {
+                for arg in &signal.args {
+                    check(&arg.signature, Position::Value);
+                }
+            }
+        }
+        assert!(code.starts_with(r#"// This file was generated by gdbus-codegen. Do not edit.
+
+gdbus::dbus_variant_type! {
+    /// A value of the D-Bus type `a{sv}`.
+    pub struct DictStringVariant("a{sv}");
+}
+
+gdbus::dbus_variant_type! {
+    /// A value of the D-Bus type `a{oa{sa{sv}}}`.
+    pub struct DictObjectPathDictStringDictStringVariant("a{oa{sa{sv}}}");
+}
+"#));
+        assert!(code.contains("    fn get_objects(&self, root: ::gdbus::variant::ObjectPath, filter: DictStringVariant) -> (DictObjectPathDictStringDictStringVariant, Vec<::gdbus::variant::ObjectPath>);\n"));
+    }
+
+    #[test]
+    fn duplicate_last_elements() {
+        let interfaces = parse(r#"
+            <node>
+              <interface name="org.example.a.Device"/>
+              <interface name="org.example.b.Device"/>
+              <interface name="org.example.Other"/>
+            </node>"#).unwrap();
+        let code = generate(&interfaces);
+        assert!(code.contains("pub trait OrgExampleADeviceProxy {"));
+        assert!(code.contains("pub trait OrgExampleBDeviceProxy {"));
+        assert!(code.contains("pub struct OrgExampleADeviceServer<T>(pub T);"));
+        assert!(code.contains("pub trait OtherProxy {"));
+        assert!(!code.contains("pub trait DeviceProxy {"));
+    }
+
+    #[test]
+    fn generate_interface() {
+        let interfaces = parse(r#"
+            <node>
+              <interface name="org.example.Player">
+                <method name="Play"><arg name="uri" type="s" direction="in"/><arg type="b" direction="out"/></method>
+                <property name="Volume" type="d" access="readwrite"/>
+                <signal name="Seeked"><arg name="position" type="x"/></signal>
+              </interface>
+            </node>"#).unwrap();
+        assert_eq!(generate(&interfaces), r#"// This file was generated by gdbus-codegen. Do not edit.
+
+/// Proxy for the `org.example.Player` interface.
+#[gdbus::proxy("org.example.Player")]
+pub trait PlayerProxy {
+    #[dbus_name("Play")]
+    fn play(&self, uri: &str) -> bool;
+    #[dbus_name("Volume")]
+    #[property]
+    fn volume(&self) -> f64;
+    #[dbus_name("Seeked")]
+    #[signal]
+    fn seeked(&self, position: i64);
+}
+
+/// Implementation of the `org.example.Player` interface.
+/// Wrap it in a `PlayerServer` to register it with `Connection::register_interface()`.
+pub trait Player {
+    fn play(&mut self, uri: &str) -> bool;
+    fn volume(&self) -> f64;
+    fn set_volume(&mut self, value: f64);
+}
+
+/// Exports a `Player` implementation on the bus.
+pub struct PlayerServer<T>(pub T);
+
+#[gdbus::interface("org.example.Player")]
+impl<T: Player + 'static> PlayerServer<T> {
+    #[dbus_name("Play")]
+    fn play(&mut self, uri: &str) -> bool {
+        self.0.play(uri)
+    }
+
+    #[dbus_name("Volume")]
+    #[property]
+    fn volume(&self) -> f64 {
+        self.0.volume()
+    }
+
+    #[dbus_name("Volume")]
+    #[property]
+    fn set_volume(&mut self, value: f64) {
+        self.0.set_volume(value)
+    }
+}
+"#);
+    }
+}
@@ -0,0 +1,221 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Parser for D-Bus introspection XML.
+
+use xml::attribute::OwnedAttribute;
+use xml::reader::{EventReader, XmlEvent};
+
+use Error;
+
+/// Access control of a property.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Access {
+    /// The property can only be read.
+    Read,
+    /// The property can be read and written.
+    ReadWrite,
+    /// The property can only be written.
+    Write,
+}
+
+/// Argument of a method or a signal.
+#[derive(Debug)]
+pub struct Arg {
+    /// The name of the argument, if any.
+    pub name: Option<String>,
+    /// The D-Bus signature of the argument.
+    pub signature: String,
+}
+
+/// D-Bus interface.
+#[derive(Debug, Default)]
+pub struct Interface {
+    /// Annotations of the interface.
+    pub annotations: Vec<(String, String)>,
+    /// Methods of the interface.
+    pub methods: Vec<Method>,
+    /// The name of the interface, e.g. `org.freedesktop.DBus`.
+    pub name: String,
+    /// Properties of the interface.
+    pub properties: Vec<Property>,
+    /// Signals of the interface.
+    pub signals: Vec<Signal>,
+}
+
+/// Method of an interface.
+#[derive(Debug, Default)]
+pub struct Method {
+    /// Annotations of the method.
+    pub annotations: Vec<(String, String)>,
+    /// Arguments sent by the caller.
+    pub in_args: Vec<Arg>,
+    /// The name of the method.
+    pub name: String,
+    /// Values sent back to the caller.
+    pub out_args: Vec<Arg>,
+}
+
+/// Property of an interface.
+#[derive(Debug)]
+pub struct Property {
+    /// Access control of the property.
+    pub access: Access,
+    /// Annotations of the property.
+    pub annotations: Vec<(String, String)>,
+    /// The name of the property.
+    pub name: String,
+    /// The D-Bus signature of the property.
+    pub signature: String,
+}
+
+/// Signal of an interface.
+#[derive(Debug, Default)]
+pub struct Signal {
+    /// Annotations of the signal.
+    pub annotations: Vec<(String, String)>,
+    /// Arguments of the signal.
+    pub args: Vec<Arg>,
+    /// The name of the signal.
+    pub name: String,
+}
+
+/// Member being parsed.
+enum Member {
+    Method(Method),
+    Property(Property),
+    Signal(Signal),
+}
+
+/// Parse the interfaces of the document, including the ones of the child nodes.
+pub fn parse(xml: &str) -> Result<Vec<Interface>, Error> {
+    let mut interfaces = vec![];
+    let mut interface: Option<Interface> = None;
+    let mut member = None;
+    for event in EventReader::from_str(xml) {
+        match event? {
+            XmlEvent::StartElement { name, attributes, .. } => {
+                match name.local_name.as_str() {
+                    "interface" => {
+                        interface = Some(Interface {
+                            name: attribute(&attributes, "interface", "name")?,
+                            .. Interface::default()
+                        });
+                    },
+                    "method" => {
+                        member = Some(Member::Method(Method {
+                            name: attribute(&attributes, "method", "name")?,
+                            .. Method::default()
+                        }));
+                    },
+                    "signal" => {
+                        member = Some(Member::Signal(Signal {
+                            name: attribute(&attributes, "signal", "name")?,
+                            .. Signal::default()
+                        }));
+                    },
+                    "property" => {
+                        let access =
+                            match attribute(&attributes, "property", "access")?.as_str() {
+                                "read" => Access::Read,
+                                "readwrite" => Access::ReadWrite,
+                                "write" => Access::Write,
+                                access => return Err(Error::Introspection(format!("unknown property access '{}'", access))),
+                            };
+                        member = Some(Member::Property(Property {
+                            access,
+                            annotations: vec![],
+                            name: attribute(&attributes, "property", "name")?,
+                            signature: attribute(&attributes, "property", "type")?,
+                        }));
+                    },
+                    "arg" => {
+                        let arg = Arg {
+                            name: optional_attribute(&attributes, "name"),
+                            signature: attribute(&attributes, "arg", "type")?,
+                        };
+                        match member {
+                            Some(Member::Method(ref mut method)) => {
+                                match optional_attribute(&attributes, "direction").as_deref() {
+                                    None | Some("in") => method.in_args.push(arg),
+                                    Some("out") => method.out_args.push(arg),
+                                    Some(direction) => return Err(Error::Introspection(format!("unknown argument direction '{}'", direction))),
+                                }
+                            },
+                            Some(Member::Signal(ref mut signal)) => signal.args.push(arg),
+                            _ => return Err(Error::Introspection("argument outside of a method or a signal".to_string())),
+                        }
+                    },
+                    "annotation" => {
+                        let annotation = (attribute(&attributes, "annotation", "name")?, attribute(&attributes, "annotation", "value")?);
+                        match member {
+                            Some(Member::Method(ref mut method)) => method.annotations.push(annotation),
+                            Some(Member::Property(ref mut property)) => property.annotations.push(annotation),
+                            Some(Member::Signal(ref mut signal)) => signal.annotations.push(annotation),
+                            None => {
+                                if let Some(ref mut interface) = interface {
+                                    interface.annotations.push(annotation);
+                                }
+                            },
+                        }
+                    },
+                    _ => (),
+                }
+            },
+            XmlEvent::EndElement { name } => {
+                match name.local_name.as_str() {
+                    "interface" => {
+                        if let Some(interface) = interface.take() {
+                            interfaces.push(interface);
+                        }
+                    },
+                    "method" | "property" | "signal" => {
+                        let interface =
+                            match interface {
+                                Some(ref mut interface) => interface,
+                                None => return Err(Error::Introspection(format!("{} outside of an interface", name.local_name))),
+                            };
+                        match member.take() {
+                            Some(Member::Method(method)) => interface.methods.push(method),
+                            Some(Member::Property(property)) => interface.properties.push(property),
+                            Some(Member::Signal(signal)) => interface.signals.push(signal),
+                            None => (),
+                        }
+                    },
+                    _ => (),
+                }
+            },
+            _ => (),
+        }
+    }
+    Ok(interfaces)
+}
+
+fn attribute(attributes: &[OwnedAttribute], element: &str, name: &str) -> Result<String, Error> {
+    optional_attribute(attributes, name)
+        .ok_or_else(|| Error::Introspection(format!("missing attribute '{}' on element '{}'", name, element)))
+}
+
+fn optional_attribute(attributes: &[OwnedAttribute], name: &str) -> Option<String> {
+    attributes.iter()
+        .find(|attribute| attribute.name.local_name == name)
+        .map(|attribute| attribute.value.clone())
+}
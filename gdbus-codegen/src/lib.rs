@@ -0,0 +1,131 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Generate `gdbus` proxies and server traits from D-Bus introspection XML.
+//!
+//! For every interface of the XML file, the generated code contains:
+//!
+//!  * a `NameProxy` struct, generated with `#[gdbus::proxy]`, to call the interface on a remote object;
+//!  * a `Name` trait to implement the interface;
+//!  * a `NameServer<T>` wrapper to register an implementation with `Connection::register_interface()`.
+//!
+//! Object paths and signatures are converted to `gdbus::variant::ObjectPath` and `gdbus::variant::Signature`, and arrays of basic types to a `Vec`.
+//! Other values, like dictionaries and structures, are wrapped in a type defined with `gdbus::dbus_variant_type!` and named after their signature, e.g. `DictStringVariant` for `a{sv}`, so that they keep their D-Bus type.
+//! Methods and signals with more than 5 arguments or return values are skipped with a comment.
+//!
+//! From a build script:
+//!
+//! ```ignore
+//! extern crate gdbus_codegen;
+//!
+//! use std::env;
+//! use std::path::Path;
+//!
+//! fn main() {
+//!     let output = Path::new(&env::var("OUT_DIR").unwrap()).join("device.rs");
+//!     gdbus_codegen::generate_file("interfaces/device.xml", output).unwrap();
+//!     println!("cargo:rerun-if-changed=interfaces/device.xml");
+//! }
+//! ```
+//!
+//! and then in the crate, which must depend on `gdbus` and `glib`:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/device.rs"));
+//! ```
+
+extern crate xml;
+
+mod generator;
+mod introspection;
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+pub use introspection::{Access, Arg, Interface, Method, Property, Signal};
+
+/// Error while generating the code.
+#[derive(Debug)]
+pub enum Error {
+    /// The XML is not a valid introspection document.
+    Introspection(String),
+    /// The input could not be read or the output could not be written.
+    Io(io::Error),
+    /// The input is not valid XML.
+    Xml(xml::reader::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match *self {
+            Error::Introspection(ref message) => write!(formatter, "invalid introspection data: {}", message),
+            Error::Io(ref error) => write!(formatter, "{}", error),
+            Error::Xml(ref error) => write!(formatter, "{}", error),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::Introspection(ref message) => message,
+            Error::Io(_) => "I/O error",
+            Error::Xml(_) => "XML error",
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<xml::reader::Error> for Error {
+    fn from(error: xml::reader::Error) -> Self {
+        Error::Xml(error)
+    }
+}
+
+/// Parse the interfaces of an introspection XML document.
+pub fn parse(xml: &str) -> Result<Vec<Interface>, Error> {
+    introspection::parse(xml)
+}
+
+/// Generate the Rust code for the interfaces of an introspection XML document.
+pub fn generate(xml: &str) -> Result<String, Error> {
+    let interfaces = parse(xml)?;
+    Ok(generator::generate(&interfaces))
+}
+
+/// Generate the Rust code for the introspection XML file `input` and write it to `output`.
+pub fn generate_file<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> Result<(), Error> {
+    let mut xml = String::new();
+    let mut file = File::open(input)?;
+    file.read_to_string(&mut xml)?;
+    let code = generate(&xml)?;
+    let mut file = File::create(output)?;
+    file.write_all(code.as_bytes())?;
+    Ok(())
+}
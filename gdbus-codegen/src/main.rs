@@ -0,0 +1,90 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Command-line interface of the code generator.
+
+extern crate gdbus_codegen;
+
+use std::env;
+use std::io::{self, Read, Write};
+use std::fs::File;
+use std::process;
+
+const USAGE: &str = "Usage: gdbus-codegen [--output FILE] [INPUT.xml]
+
+Generate gdbus proxies and server traits from D-Bus introspection XML.
+The XML is read from the standard input when INPUT.xml is not given, and the code is written to the standard output when FILE is not given.";
+
+fn main() {
+    let mut input = None;
+    let mut output = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            },
+            "-o" | "--output" => {
+                match args.next() {
+                    Some(file) => output = Some(file),
+                    None => usage_error(),
+                }
+            },
+            _ if input.is_none() && !arg.starts_with('-') => input = Some(arg),
+            _ => usage_error(),
+        }
+    }
+
+    if let Err(error) = run(input, output) {
+        eprintln!("gdbus-codegen: {}", error);
+        process::exit(1);
+    }
+}
+
+fn run(input: Option<String>, output: Option<String>) -> Result<(), gdbus_codegen::Error> {
+    let mut xml = String::new();
+    match input {
+        Some(input) => {
+            let mut file = File::open(input)?;
+            file.read_to_string(&mut xml)?;
+        },
+        None => {
+            io::stdin().read_to_string(&mut xml)?;
+        },
+    }
+    let code = gdbus_codegen::generate(&xml)?;
+    match output {
+        Some(output) => {
+            let mut file = File::create(output)?;
+            file.write_all(code.as_bytes())?;
+        },
+        None => {
+            io::stdout().write_all(code.as_bytes())?;
+        },
+    }
+    Ok(())
+}
+
+fn usage_error() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
+}
@@ -0,0 +1,190 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Helper attributes shared by `#[interface]` and `#[proxy]`.
+
+use proc_macro2::TokenStream;
+use syn::{Attribute, Error, FnArg, Ident, LitStr, Pat, ReturnType, Signature, Type};
+use syn::parse::{Parse, ParseStream};
+
+/// An `#[annotation("name" = "value")]` attribute.
+pub struct Annotation {
+    name: LitStr,
+    value: LitStr,
+}
+
+impl Parse for Annotation {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(Annotation {
+            name,
+            value,
+        })
+    }
+}
+
+/// Generate the `&[(name, value)]` slice given to the introspection builders.
+pub fn annotations_slice(annotations: &[Annotation]) -> TokenStream {
+    let names = annotations.iter().map(|annotation| &annotation.name);
+    let values = annotations.iter().map(|annotation| &annotation.value);
+    quote! {
+        &[#((#names, #values)),*]
+    }
+}
+
+/// Remove the `#[annotation(...)]` attributes from `attrs` and parse them.
+pub fn take_annotations(attrs: &mut Vec<Attribute>) -> syn::Result<Vec<Annotation>> {
+    let mut annotations = vec![];
+    for attr in take_attributes(attrs, "annotation") {
+        annotations.push(attr.parse_args()?);
+    }
+    Ok(annotations)
+}
+
+/// Remove the `#[dbus_name("Name")]` attribute from `attrs` and return the name it gives to a member.
+pub fn take_dbus_name(attrs: &mut Vec<Attribute>) -> syn::Result<Option<String>> {
+    match take_attribute(attrs, "dbus_name")? {
+        Some(attr) => {
+            let name: LitStr = attr.parse_args()?;
+            Ok(Some(name.value()))
+        },
+        None => Ok(None),
+    }
+}
+
+/// Remove the attribute `name` from `attrs`.
+pub fn take_attribute(attrs: &mut Vec<Attribute>, name: &str) -> syn::Result<Option<Attribute>> {
+    let mut taken = take_attributes(attrs, name);
+    if taken.len() > 1 {
+        return Err(Error::new_spanned(&taken[1], format!("duplicate #[{}] attribute", name)));
+    }
+    Ok(taken.pop())
+}
+
+fn take_attributes(attrs: &mut Vec<Attribute>, name: &str) -> Vec<Attribute> {
+    let (taken, kept) = attrs.drain(..).partition(|attr| attr.path().is_ident(name));
+    *attrs = kept;
+    taken
+}
+
+/// Check that `interface_name` is a valid D-Bus interface name.
+pub fn validate_interface_name(interface_name: &LitStr) -> syn::Result<()> {
+    let name = interface_name.value();
+    let valid_element = |element: &str| {
+        match element.chars().next() {
+            Some(first) if !first.is_ascii_digit() => element.chars().all(|character| character.is_ascii_alphanumeric() || character == '_'),
+            _ => false,
+        }
+    };
+    if name.len() > 255 || name.split('.').count() < 2 || !name.split('.').all(valid_element) {
+        return Err(Error::new_spanned(interface_name, format!("'{}' is not a valid D-Bus interface name", name)));
+    }
+    Ok(())
+}
+
+/// Kind of receiver of a method.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Receiver {
+    None,
+    Ref,
+    Mut,
+}
+
+/// The receiver and the named arguments of a method.
+pub struct Arguments {
+    pub receiver: Receiver,
+    pub names: Vec<Ident>,
+    pub types: Vec<Type>,
+}
+
+/// Parse the receiver and the arguments of `signature`, rejecting the ones that cannot be exported.
+pub fn arguments(signature: &Signature) -> syn::Result<Arguments> {
+    if let Some(ref asyncness) = signature.asyncness {
+        return Err(Error::new_spanned(asyncness, "async methods are not supported: return a `gdbus::future::Async` instead"));
+    }
+    if !signature.generics.params.is_empty() || signature.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(&signature.generics, "generic methods cannot be exported on D-Bus"));
+    }
+    if let Some(ref variadic) = signature.variadic {
+        return Err(Error::new_spanned(variadic, "variadic methods cannot be exported on D-Bus"));
+    }
+    let mut arguments = Arguments {
+        receiver: Receiver::None,
+        names: vec![],
+        types: vec![],
+    };
+    for input in &signature.inputs {
+        match *input {
+            FnArg::Receiver(ref receiver) => {
+                if receiver.reference.is_none() || receiver.colon_token.is_some() {
+                    return Err(Error::new_spanned(receiver, "expected `&self` or `&mut self`"));
+                }
+                arguments.receiver =
+                    if receiver.mutability.is_some() {
+                        Receiver::Mut
+                    }
+                    else {
+                        Receiver::Ref
+                    };
+            },
+            FnArg::Typed(ref argument) => {
+                match *argument.pat {
+                    Pat::Ident(ref pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                        arguments.names.push(pat.ident.clone());
+                        arguments.types.push((*argument.ty).clone());
+                    },
+                    _ => return Err(Error::new_spanned(&argument.pat, "expected an argument name")),
+                }
+            },
+        }
+    }
+    Ok(arguments)
+}
+
+/// The return type of `signature`, `None` for `()`.
+pub fn return_type(signature: &Signature) -> Option<Type> {
+    match signature.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ref return_type) => {
+            match **return_type {
+                Type::Tuple(ref tuple) if tuple.elems.is_empty() => None,
+                _ => Some((**return_type).clone()),
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::Signature;
+
+    use super::arguments;
+
+    // Not a UI test: rustc rejects `async fn` in Rust 2015 with a hint naming the latest edition before the attributes run.
+    #[test]
+    fn async_methods() {
+        let signature: Signature = parse_quote!(async fn ping(&self));
+        let error = arguments(&signature).err().expect("async method accepted");
+        assert_eq!(error.to_string(), "async methods are not supported: return a `gdbus::future::Async` instead");
+    }
+}
@@ -0,0 +1,248 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Expansion of the `#[interface]` attribute.
+
+use proc_macro2::TokenStream;
+use syn::{Error, Ident, ImplItem, ImplItemFn, ItemImpl, LitStr, Type};
+use syn::ext::IdentExt;
+
+use attributes::{self, Annotation, Receiver};
+
+struct Method {
+    annotations: Vec<Annotation>,
+    arg_names: Vec<Ident>,
+    arg_types: Vec<Type>,
+    dbus_name: String,
+    name: Ident,
+    receiver: Receiver,
+    return_type: Option<Type>,
+}
+
+struct Property {
+    annotations: Vec<Annotation>,
+    getter: Option<Ident>,
+    name: String,
+    property_type: Type,
+    setter: Option<Ident>,
+}
+
+pub fn expand(interface_name: LitStr, mut item: ItemImpl) -> syn::Result<TokenStream> {
+    attributes::validate_interface_name(&interface_name)?;
+    if let Some((_, ref path, _)) = item.trait_ {
+        return Err(Error::new_spanned(path, "#[interface] must be used on an inherent impl block"));
+    }
+    let interface_annotations = attributes::take_annotations(&mut item.attrs)?;
+
+    let mut methods = vec![];
+    let mut properties: Vec<Property> = vec![];
+    for impl_item in &mut item.items {
+        if let ImplItem::Fn(ref mut function) = *impl_item {
+            let annotations = attributes::take_annotations(&mut function.attrs)?;
+            let dbus_name = attributes::take_dbus_name(&mut function.attrs)?;
+            if attributes::take_attribute(&mut function.attrs, "property")?.is_some() {
+                add_property(&mut properties, function, dbus_name, annotations)?;
+            }
+            else {
+                let arguments = attributes::arguments(&function.sig)?;
+                methods.push(Method {
+                    annotations,
+                    arg_names: arguments.names,
+                    arg_types: arguments.types,
+                    dbus_name: dbus_name.unwrap_or_else(|| function.sig.ident.unraw().to_string()),
+                    name: function.sig.ident.clone(),
+                    receiver: arguments.receiver,
+                    return_type: attributes::return_type(&function.sig),
+                });
+            }
+        }
+    }
+
+    let method_infos = methods.iter().map(method_info);
+    let method_calls = methods.iter().map(method_call);
+    let property_infos = properties.iter().map(property_info);
+    let readable: Vec<_> = properties.iter().filter_map(|property| property.getter.as_ref().map(|getter| (&property.name, getter))).collect();
+    let readable_names: Vec<_> = readable.iter().map(|&(name, _)| name).collect();
+    let getters: Vec<_> = readable.iter().map(|&(_, getter)| getter).collect();
+    let writable: Vec<_> = properties.iter().filter_map(|property| property.setter.as_ref().map(|setter| (&property.name, setter))).collect();
+    let writable_names = writable.iter().map(|&(name, _)| name);
+    let setters = writable.iter().map(|&(_, setter)| setter);
+    let interface_annotations = attributes::annotations_slice(&interface_annotations);
+
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_type = &item.self_ty;
+    Ok(quote! {
+        #item
+
+        impl #impl_generics ::gdbus::interface::Interface for #self_type #where_clause {
+            const INTERFACE_NAME: &'static str = #interface_name;
+
+            fn interface_info() -> Result<::gdbus::node_info::InterfaceInfo, ::gdbus::Error> {
+                let mut interface = ::gdbus::node_info::InterfaceInfo::builder(#interface_name);
+                for &(name, value) in #interface_annotations as &[(&str, &str)] {
+                    interface = interface.annotation(name, value);
+                }
+                interface
+                    #(.method(#method_infos))*
+                    #(.property(#property_infos))*
+                    .build()
+            }
+
+            fn call_method<F: FnOnce() + 'static>(_object: &::std::rc::Rc<::std::cell::RefCell<Self>>, method_name: &str, _args: ::gdbus::variant::Variant, invocation: ::gdbus::method_invocation::MethodInvocation, finished: F) {
+                let finished =
+                    match method_name {
+                        #(#method_calls)*
+                        _ => {
+                            invocation.return_dbus_error("org.freedesktop.DBus.Error.UnknownMethod", &format!("Unknown method {}", method_name));
+                            Some(finished)
+                        },
+                    };
+                if let Some(finished) = finished {
+                    finished();
+                }
+            }
+
+            fn get_property(&self, property_name: &str) -> Option<::gdbus::variant::Variant> {
+                match property_name {
+                    #(#readable_names => Some(::gdbus::variant::Variant::from_value(&self.#getters())),)*
+                    _ => None,
+                }
+            }
+
+            fn set_property(&mut self, property_name: &str, _value: ::gdbus::variant::Variant) -> bool {
+                match property_name {
+                    #(#writable_names => {
+                        self.#setters(_value.get_value());
+                        true
+                    },)*
+                    _ => false,
+                }
+            }
+
+            fn property_values(&self) -> Vec<(String, ::gdbus::variant::Variant)> {
+                vec![#((#readable_names.to_string(), ::gdbus::variant::Variant::from_value(&self.#getters()))),*]
+            }
+        }
+    })
+}
+
+/// Add the getter or setter `function` to its property.
+/// The property is named after the getter, or after the setter without its `set_` prefix, unless `dbus_name` is given.
+fn add_property(properties: &mut Vec<Property>, function: &ImplItemFn, dbus_name: Option<String>, annotations: Vec<Annotation>) -> syn::Result<()> {
+    let arguments = attributes::arguments(&function.sig)?;
+    let return_type = attributes::return_type(&function.sig);
+    let function_name = function.sig.ident.unraw().to_string();
+    let (name, property_type, is_getter) =
+        match (arguments.receiver, arguments.types.len(), return_type) {
+            (Receiver::Ref, 0, Some(return_type)) => (function_name, return_type, true),
+            (Receiver::Mut, 1, None) if function_name.starts_with("set_") => (function_name["set_".len()..].to_string(), arguments.types[0].clone(), false),
+            _ => return Err(Error::new_spanned(&function.sig, "a property must be a getter `fn name(&self) -> T` or a setter `fn set_name(&mut self, value: T)`")),
+        };
+    let name = dbus_name.unwrap_or(name);
+    let index =
+        match properties.iter().position(|property| property.name == name) {
+            Some(index) => index,
+            None => {
+                properties.push(Property {
+                    annotations: vec![],
+                    getter: None,
+                    name,
+                    property_type,
+                    setter: None,
+                });
+                properties.len() - 1
+            },
+        };
+    let property = &mut properties[index];
+    let accessor = if is_getter { &mut property.getter } else { &mut property.setter };
+    if accessor.is_some() {
+        return Err(Error::new_spanned(&function.sig.ident, format!("duplicate {} for property `{}`", if is_getter { "getter" } else { "setter" }, property.name)));
+    }
+    *accessor = Some(function.sig.ident.clone());
+    property.annotations.extend(annotations);
+    Ok(())
+}
+
+fn method_info(method: &Method) -> TokenStream {
+    let name = &method.dbus_name;
+    let arg_names = method.arg_names.iter().map(|arg_name| arg_name.unraw().to_string());
+    let arg_types = &method.arg_types;
+    let return_type = method.return_type.as_ref().map(|return_type| quote!(#return_type)).unwrap_or_else(|| quote!(()));
+    let annotations = attributes::annotations_slice(&method.annotations);
+    quote! {
+        ::gdbus::interface::method_info(#name,
+            &[#((#arg_names, <#arg_types as ::gdbus::variant::ToFormat>::to_format())),*],
+            <#return_type as ::gdbus::method_invocation::MethodResult>::out_signatures(),
+            #annotations)
+    }
+}
+
+fn method_call(method: &Method) -> TokenStream {
+    let name = &method.dbus_name;
+    let function = &method.name;
+    let arg_names = &method.arg_names;
+    let arg_types = &method.arg_types;
+    let decode_args =
+        if arg_names.is_empty() {
+            quote!()
+        }
+        else {
+            quote! {
+                let (#(#arg_names,)*): (#(#arg_types,)*) = ::gdbus::variant::FromVariant::from_variant(&_args);
+            }
+        };
+    let call =
+        match method.receiver {
+            Receiver::None => quote! {
+                let result = Self::#function(#(#arg_names),*);
+            },
+            Receiver::Ref => quote! {
+                let this = _object.borrow();
+                let result = this.#function(#(#arg_names),*);
+            },
+            Receiver::Mut => quote! {
+                let mut this = _object.borrow_mut();
+                let result = this.#function(#(#arg_names),*);
+            },
+        };
+    quote! {
+        #name => {
+            #decode_args
+            #call
+            ::gdbus::method_invocation::MethodResult::return_to_then(result, invocation, finished)
+        },
+    }
+}
+
+fn property_info(property: &Property) -> TokenStream {
+    let name = &property.name;
+    let property_type = &property.property_type;
+    let flags =
+        match (&property.getter, &property.setter) {
+            (&Some(_), &None) => quote!(::gdbus::node_info::PROPERTY_INFO_FLAGS_READABLE),
+            (&None, _) => quote!(::gdbus::node_info::PROPERTY_INFO_FLAGS_WRITABLE),
+            (&Some(_), &Some(_)) => quote!(::gdbus::node_info::PROPERTY_INFO_FLAGS_READABLE | ::gdbus::node_info::PROPERTY_INFO_FLAGS_WRITABLE),
+        };
+    let annotations = attributes::annotations_slice(&property.annotations);
+    quote! {
+        ::gdbus::interface::property_info(#name, <#property_type as ::gdbus::variant::ToFormat>::to_format(), #flags, #annotations)
+    }
+}
@@ -0,0 +1,76 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Attributes generating D-Bus servers and proxies for the `gdbus` crate.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+mod attributes;
+mod interface;
+mod properties;
+mod proxy;
+
+use proc_macro::TokenStream;
+use syn::{Error, ItemImpl, ItemTrait, LitStr};
+
+/// Export the methods of an inherent impl block as the D-Bus interface whose name is given as argument.
+/// The type can then be registered with `Connection::register_interface()`.
+#[proc_macro_attribute]
+pub fn interface(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let interface_name = parse_macro_input!(attr as LitStr);
+    let item = parse_macro_input!(item as ItemImpl);
+    interface::expand(interface_name, item)
+        .unwrap_or_else(compile_error)
+        .into()
+}
+
+/// Replace a trait by a struct calling its methods on a remote object implementing the D-Bus interface whose name is given as argument.
+#[proc_macro_attribute]
+pub fn proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let interface_name = parse_macro_input!(attr as LitStr);
+    let item = parse_macro_input!(item as ItemTrait);
+    proxy::expand(interface_name, item)
+        .unwrap_or_else(compile_error)
+        .into()
+}
+
+/// Generate the `ProxyNameProperties` struct and the `get_all()` method of a proxy created by `dbus_interface!`.
+#[doc(hidden)]
+#[proc_macro]
+pub fn dbus_proxy_properties(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as properties::Properties);
+    properties::expand(input).into()
+}
+
+/// Like `Error::to_compile_error()`, but without the `::core` path which cannot be resolved from the 2015 edition.
+fn compile_error(error: Error) -> proc_macro2::TokenStream {
+    error.into_iter()
+        .map(|error| {
+            let message = error.to_string();
+            quote_spanned!(error.span()=> compile_error!(#message);)
+        })
+        .collect()
+}
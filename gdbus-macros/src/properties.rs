@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Expansion of the properties of the `dbus_interface!` macro, which cannot build the name of the struct itself.
+
+use proc_macro2::TokenStream;
+use syn::{Expr, Ident, Type};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+
+struct Property {
+    name: Ident,
+    property_type: Type,
+}
+
+impl Parse for Property {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        Ok(Property {
+            name,
+            property_type: input.parse()?,
+        })
+    }
+}
+
+/// Input of the macro: `ProxyName, "interface.name", [name: Type, ...]`.
+pub struct Properties {
+    interface_name: Expr,
+    properties: Punctuated<Property, Token![,]>,
+    proxy_name: Ident,
+}
+
+impl Parse for Properties {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let proxy_name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let interface_name = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let content;
+        bracketed!(content in input);
+        Ok(Properties {
+            interface_name,
+            properties: content.parse_terminated(Property::parse, Token![,])?,
+            proxy_name,
+        })
+    }
+}
+
+pub fn expand(input: Properties) -> TokenStream {
+    let proxy_name = &input.proxy_name;
+    let interface_name = &input.interface_name;
+    let properties_name = format_ident!("{}Properties", proxy_name);
+    let names: Vec<_> = input.properties.iter().map(|property| &property.name).collect();
+    let keys = names.iter().map(|name| name.to_string());
+    let types = input.properties.iter().map(|property| &property.property_type);
+    quote! {
+        /// Values of the properties returned by `get_all()`, `None` when the remote object did not send the property.
+        pub struct #properties_name {
+            #(pub #names: Option<#types>,)*
+        }
+
+        impl #proxy_name {
+            pub fn get_all(&self) -> Result<#properties_name, ::gdbus::Error> {
+                let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, "org.freedesktop.DBus.Properties", "GetAll");
+                method_call_message.set_body((#interface_name,));
+                self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
+                    .map(|message| {
+                        let _properties = message.get_body().child_value(0);
+                        #properties_name {
+                            #(#names: _properties.lookup_value(#keys).map(|value| value.get_value()),)*
+                        }
+                    })
+            }
+        }
+    }
+}
@@ -0,0 +1,303 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Expansion of the `#[proxy]` attribute.
+
+use proc_macro2::TokenStream;
+use syn::{Attribute, Error, Ident, ItemTrait, LitStr, TraitItem, TraitItemFn, Type};
+use syn::ext::IdentExt;
+
+use attributes::{self, Receiver};
+
+#[derive(PartialEq)]
+enum Access {
+    Read,
+    ReadWrite,
+    Write,
+}
+
+pub fn expand(interface_name: LitStr, item: ItemTrait) -> syn::Result<TokenStream> {
+    attributes::validate_interface_name(&interface_name)?;
+    if !item.generics.params.is_empty() || item.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(&item.generics, "#[proxy] cannot be used on a generic trait"));
+    }
+    if !item.supertraits.is_empty() {
+        return Err(Error::new_spanned(&item.supertraits, "#[proxy] cannot be used on a trait with supertraits"));
+    }
+    if let Some(ref unsafety) = item.unsafety {
+        return Err(Error::new_spanned(unsafety, "#[proxy] cannot be used on an unsafe trait"));
+    }
+
+    let mut functions = vec![];
+    let mut property_keys = vec![];
+    let mut property_names = vec![];
+    let mut property_types = vec![];
+    for trait_item in item.items {
+        let mut function =
+            match trait_item {
+                TraitItem::Fn(function) => function,
+                _ => return Err(Error::new_spanned(trait_item, "#[proxy] traits can only contain methods")),
+            };
+        if let Some(ref default) = function.default {
+            return Err(Error::new_spanned(default, "proxy methods cannot have a body"));
+        }
+        if attributes::arguments(&function.sig)?.receiver != Receiver::Ref {
+            return Err(Error::new(function.sig.paren_token.span.join(), "expected `&self`"));
+        }
+        let dbus_name = attributes::take_dbus_name(&mut function.attrs)?.unwrap_or_else(|| function.sig.ident.unraw().to_string());
+        let property = attributes::take_attribute(&mut function.attrs, "property")?;
+        let signal = attributes::take_attribute(&mut function.attrs, "signal")?;
+        if let Some(attr) = attributes::take_attribute(&mut function.attrs, "annotation")? {
+            return Err(Error::new_spanned(attr, "annotations are only supported by #[interface]"));
+        }
+        let generated =
+            match (property, signal) {
+                (Some(property), None) => {
+                    let access = property_access(&property)?;
+                    let property_type = property_type(&function)?;
+                    // Write-only properties are not returned by GetAll.
+                    if access != Access::Write {
+                        property_names.push(function.sig.ident.clone());
+                        property_keys.push(dbus_name.clone());
+                        property_types.push(property_type.clone());
+                    }
+                    property_functions(&interface_name, &dbus_name, &function, property_type, access)
+                },
+                (None, Some(_)) => signal_function(&interface_name, &dbus_name, &function)?,
+                (None, None) => method_functions(&interface_name, &dbus_name, &function)?,
+                (Some(_), Some(signal)) => return Err(Error::new_spanned(signal, "a method cannot be both a property and a signal")),
+            };
+        functions.push(generated);
+    }
+
+    let attrs = &item.attrs;
+    let visibility = &item.vis;
+    let name = &item.ident;
+    let properties_name = format_ident!("{}Properties", name);
+    Ok(quote! {
+        #(#attrs)*
+        #visibility struct #name {
+            connection: ::gdbus::connection::Connection,
+            dbus_name: String,
+            object_path: String,
+        }
+
+        impl #name {
+            pub fn new(dbus_name: &str, object_path: &str) -> Result<Self, ::gdbus::Error> {
+                #name::new_for_bus(::gdbus::own_name::Type::Session, dbus_name, object_path)
+            }
+
+            pub fn new_for_bus(bus_type: ::gdbus::own_name::Type, dbus_name: &str, object_path: &str) -> Result<Self, ::gdbus::Error> {
+                let connection = ::gdbus::connection::Connection::get_sync(bus_type)?;
+                Ok(#name::new_for_connection(&connection, dbus_name, object_path))
+            }
+
+            pub fn new_for_connection(connection: &::gdbus::connection::Connection, dbus_name: &str, object_path: &str) -> Self {
+                #name {
+                    connection: connection.clone(),
+                    dbus_name: dbus_name.to_string(),
+                    object_path: object_path.to_string(),
+                }
+            }
+
+            pub fn get_all(&self) -> Result<#properties_name, ::gdbus::Error> {
+                let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, "org.freedesktop.DBus.Properties", "GetAll");
+                method_call_message.set_body((#interface_name,));
+                self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
+                    .map(|message| {
+                        let _properties = message.get_body().child_value(0);
+                        #properties_name {
+                            #(#property_names: _properties.lookup_value(#property_keys).map(|value| value.get_value()),)*
+                        }
+                    })
+            }
+
+            #(#functions)*
+        }
+
+        impl ::gdbus::interface::InterfaceProxy for #name {
+            const INTERFACE_NAME: &'static str = #interface_name;
+
+            fn new_for_connection(connection: &::gdbus::connection::Connection, dbus_name: &str, object_path: &str) -> Self {
+                #name::new_for_connection(connection, dbus_name, object_path)
+            }
+        }
+
+        /// Values of the properties returned by `get_all()`, `None` when the remote object did not send the property.
+        #visibility struct #properties_name {
+            #(pub #property_names: Option<#property_types>,)*
+        }
+    })
+}
+
+/// Get the access of a property from its `#[property]`, `#[property(read)]` or `#[property(write)]` attribute.
+fn property_access(attr: &Attribute) -> syn::Result<Access> {
+    if let ::syn::Meta::Path(_) = attr.meta {
+        return Ok(Access::ReadWrite);
+    }
+    let access: Ident = attr.parse_args()?;
+    if access == "read" {
+        Ok(Access::Read)
+    }
+    else if access == "write" {
+        Ok(Access::Write)
+    }
+    else {
+        Err(Error::new_spanned(access, "expected `read` or `write`"))
+    }
+}
+
+fn property_type(function: &TraitItemFn) -> syn::Result<Type> {
+    let arguments = attributes::arguments(&function.sig)?;
+    match attributes::return_type(&function.sig) {
+        Some(property_type) => {
+            if arguments.names.is_empty() {
+                Ok(property_type)
+            }
+            else {
+                Err(Error::new_spanned(&function.sig.inputs, "a property getter cannot have arguments"))
+            }
+        },
+        None => Err(Error::new_spanned(&function.sig, "a property must be declared as `fn name(&self) -> T`")),
+    }
+}
+
+fn property_functions(interface_name: &LitStr, key: &str, function: &TraitItemFn, property_type: Type, access: Access) -> TokenStream {
+    let attrs = &function.attrs;
+    let name = &function.sig.ident;
+    let getter = quote! {
+        #(#attrs)*
+        pub fn #name(&self) -> Result<#property_type, ::gdbus::Error> {
+            let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, "org.freedesktop.DBus.Properties", "Get");
+            method_call_message.set_body((#interface_name, #key));
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
+                .map(|message| {
+                    let (value,): (::gdbus::variant::Variant,) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
+                    value.get_value()
+                })
+        }
+    };
+    let setter_name = format_ident!("set_{}", name.unraw());
+    let setter = quote! {
+        pub fn #setter_name(&self, value: #property_type) -> Result<(), ::gdbus::Error> {
+            let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, "org.freedesktop.DBus.Properties", "Set");
+            method_call_message.set_body((#interface_name, #key, ::gdbus::variant::Variant::from_value(&value)));
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
+                .map(|_| ())
+        }
+    };
+    match access {
+        Access::Read => getter,
+        Access::ReadWrite => quote! {
+            #getter
+
+            #setter
+        },
+        Access::Write => quote! {
+            #(#attrs)*
+            #setter
+        },
+    }
+}
+
+fn signal_function(interface_name: &LitStr, name: &str, function: &TraitItemFn) -> syn::Result<TokenStream> {
+    let arguments = attributes::arguments(&function.sig)?;
+    if attributes::return_type(&function.sig).is_some() {
+        return Err(Error::new_spanned(&function.sig.output, "a signal cannot have a return type"));
+    }
+    let attrs = &function.attrs;
+    let connect = format_ident!("connect_{}", function.sig.ident.unraw());
+    let arg_names = &arguments.names;
+    let arg_types = &arguments.types;
+    Ok(quote! {
+        #(#attrs)*
+        pub fn #connect<F: Fn(#(#arg_types),*) + 'static>(&self, callback: F) -> ::gdbus::connection::SignalSubscription {
+            self.connection.signal_subscribe(Some(&self.dbus_name), Some(#interface_name), Some(#name), Some(&self.object_path), None, ::gdbus::connection::SIGNAL_FLAGS_NONE,
+                move |_, _, _, _, _, _parameters| {
+                    let (#(#arg_names,)*): (#(#arg_types,)*) = ::gdbus::variant::FromVariant::from_variant(&_parameters);
+                    callback(#(#arg_names),*);
+                })
+        }
+    })
+}
+
+/// Generate the synchronous, callback and future versions of a method.
+fn method_functions(interface_name: &LitStr, method_name: &str, function: &TraitItemFn) -> syn::Result<TokenStream> {
+    let arguments = attributes::arguments(&function.sig)?;
+    let attrs = &function.attrs;
+    let name = &function.sig.ident;
+    let async_name = format_ident!("{}_async", name.unraw());
+    let future_name = format_ident!("{}_future", name.unraw());
+    let arg_names = &arguments.names;
+    let arg_types = &arguments.types;
+    let return_type = attributes::return_type(&function.sig);
+    let result_type = return_type.as_ref().map(|return_type| quote!(#return_type)).unwrap_or_else(|| quote!(()));
+    let decode_reply =
+        match return_type {
+            None => quote!(()),
+            Some(Type::Tuple(ref tuple)) => quote! {{
+                let response: #tuple = ::gdbus::variant::FromVariant::from_variant(&_message.get_body());
+                response
+            }},
+            Some(ref return_type) => quote! {{
+                let (response,): (#return_type,) = ::gdbus::variant::FromVariant::from_variant(&_message.get_body());
+                response
+            }},
+        };
+    let sync_call =
+        if return_type.is_none() {
+            quote! {
+                self.connection.send_message(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            }
+        }
+        else {
+            quote! {
+                self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
+                    .map(|_message| #decode_reply)
+            }
+        };
+    let async_doc = format!("Like `{}()` but calls `callback` with the result instead of blocking.", name.unraw());
+    let future_doc = format!("Like `{}()` but returns a future resolving to the result.", name.unraw());
+    Ok(quote! {
+        #(#attrs)*
+        pub fn #name(&self, #(#arg_names: #arg_types),*) -> Result<#result_type, ::gdbus::Error> {
+            let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, #interface_name, #method_name);
+            method_call_message.set_body((#(#arg_names,)*));
+            #sync_call
+        }
+
+        #[doc = #async_doc]
+        pub fn #async_name<F: FnOnce(Result<#result_type, ::gdbus::Error>) + 'static>(&self, #(#arg_names: #arg_types,)* callback: F) {
+            let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, #interface_name, #method_name);
+            method_call_message.set_body((#(#arg_names,)*));
+            self.connection.send_message_with_reply(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, move |result| {
+                callback(result.map(|_message| #decode_reply))
+            });
+        }
+
+        #[doc = #future_doc]
+        pub fn #future_name(&self, #(#arg_names: #arg_types),*) -> ::gdbus::future::Reply<#result_type> {
+            let (sender, reply) = ::gdbus::future::channel();
+            self.#async_name(#(#arg_names,)* move |result| sender.send(result));
+            reply
+        }
+    })
+}
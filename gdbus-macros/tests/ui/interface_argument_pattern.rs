@@ -0,0 +1,15 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("org.example.Object")]
+impl Object {
+    fn add(&self, (first, second): (i32, i32)) -> i32 {
+        first + second
+    }
+}
+
+fn main() {
+}
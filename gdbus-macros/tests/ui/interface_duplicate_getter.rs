@@ -0,0 +1,22 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("org.example.Object")]
+impl Object {
+    #[property]
+    fn volume(&self) -> f64 {
+        0.0
+    }
+
+    #[dbus_name("volume")]
+    #[property]
+    fn level(&self) -> f64 {
+        0.0
+    }
+}
+
+fn main() {
+}
@@ -0,0 +1,15 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("org.example.Object")]
+impl Object {
+    fn echo<T>(&self, value: T) -> T {
+        value
+    }
+}
+
+fn main() {
+}
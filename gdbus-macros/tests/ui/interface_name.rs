@@ -0,0 +1,14 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("example")]
+impl Object {
+    fn ping(&self) {
+    }
+}
+
+fn main() {
+}
@@ -0,0 +1,16 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("org.example.Object")]
+impl Object {
+    #[property]
+    fn volume(&self, channel: u32) -> f64 {
+        channel as f64
+    }
+}
+
+fn main() {
+}
@@ -0,0 +1,14 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("org.example.Object")]
+impl Object {
+    fn close(self) {
+    }
+}
+
+fn main() {
+}
@@ -0,0 +1,15 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("org.example.Object")]
+impl Clone for Object {
+    fn clone(&self) -> Self {
+        Object
+    }
+}
+
+fn main() {
+}
@@ -0,0 +1,15 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::interface;
+
+struct Object;
+
+#[interface("org.example.Object")]
+impl Object {
+    unsafe extern "C" fn sum(&self, count: u32, _: ...) -> u32 {
+        count
+    }
+}
+
+fn main() {
+}
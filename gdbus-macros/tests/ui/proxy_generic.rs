@@ -0,0 +1,11 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::proxy;
+
+#[proxy("org.example.Object")]
+trait ObjectProxy<T> {
+    fn ping(&self);
+}
+
+fn main() {
+}
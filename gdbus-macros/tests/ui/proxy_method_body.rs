@@ -0,0 +1,12 @@
+extern crate gdbus_macros;
+
+use gdbus_macros::proxy;
+
+#[proxy("org.example.Object")]
+trait ObjectProxy {
+    fn ping(&self) {
+    }
+}
+
+fn main() {
+}
@@ -0,0 +1,201 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Client of the `org.freedesktop.DBus` interface of the message bus.
+
+use glib::error::Error;
+
+use connection::{Connection, SignalSubscription, SEND_MESSAGE_FLAGS_NONE, SIGNAL_FLAGS_NONE};
+use message::Message;
+use own_name::Type;
+use variant::{FromFFI, FromFormat, FromVariant, ToVariant, Variant};
+
+/// The well-known name of the message bus.
+pub const DBUS_NAME: &'static str = "org.freedesktop.DBus";
+/// The name of the interface of the message bus.
+pub const DBUS_INTERFACE: &'static str = "org.freedesktop.DBus";
+/// The object path of the message bus.
+pub const DBUS_PATH: &'static str = "/org/freedesktop/DBus";
+
+/// Credentials of a connection, as returned by `DBusDaemon::get_connection_credentials()`.
+/// A field is `None` when the message bus does not know its value.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Credentials {
+    /// The security label of the process, as defined by the Linux security module.
+    pub linux_security_label: Option<Vec<u8>>,
+    /// The process ID.
+    pub process_id: Option<u32>,
+    /// The group IDs of the process.
+    pub unix_group_ids: Option<Vec<u32>>,
+    /// The user ID of the process.
+    pub unix_user_id: Option<u32>,
+    /// The Windows security identifier of the process.
+    pub windows_sid: Option<String>,
+}
+
+/// Result of `DBusDaemon::start_service_by_name()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StartServiceReply {
+    /// The service was started.
+    Success,
+    /// A connection already owns the name.
+    AlreadyRunning,
+}
+
+/// Proxy for the message bus.
+pub struct DBusDaemon {
+    connection: Connection,
+}
+
+impl DBusDaemon {
+    /// Create a proxy for the session bus.
+    pub fn new() -> Result<Self, Error> {
+        DBusDaemon::new_for_bus(Type::Session)
+    }
+
+    /// Create a proxy for the bus specified by `bus_type`.
+    pub fn new_for_bus(bus_type: Type) -> Result<Self, Error> {
+        let connection = try!(Connection::get_sync(bus_type));
+        Ok(DBusDaemon::new_for_connection(&connection))
+    }
+
+    /// Create a proxy for the message bus `connection` is connected to.
+    pub fn new_for_connection(connection: &Connection) -> Self {
+        DBusDaemon {
+            connection: connection.clone(),
+        }
+    }
+
+    /// Adds a match rule to receive the messages matching it, e.g. `type='signal',interface='org.example.Interface'`.
+    pub fn add_match(&self, rule: &str) -> Result<(), Error> {
+        self.call("AddMatch", (rule,)).map(|_| ())
+    }
+
+    /// Gets the credentials of the connection owning `name`.
+    pub fn get_connection_credentials(&self, name: &str) -> Result<Credentials, Error> {
+        self.call("GetConnectionCredentials", (name,))
+            .map(|body| {
+                let credentials = body.child_value(0);
+                Credentials {
+                    linux_security_label: credentials.lookup_value("LinuxSecurityLabel").map(|label| array_values(&label)),
+                    process_id: credentials.lookup_value("ProcessID").map(|id| id.get_value()),
+                    unix_group_ids: credentials.lookup_value("UnixGroupIDs").map(|ids| array_values(&ids)),
+                    unix_user_id: credentials.lookup_value("UnixUserID").map(|id| id.get_value()),
+                    windows_sid: credentials.lookup_value("WindowsSID").map(|sid| sid.get_value()),
+                }
+            })
+    }
+
+    /// Gets the Unix process ID of the connection owning `name`.
+    pub fn get_connection_unix_process_id(&self, name: &str) -> Result<u32, Error> {
+        self.call("GetConnectionUnixProcessID", (name,)).map(|body| body.child_value(0).get_value())
+    }
+
+    /// Gets the Unix user ID of the connection owning `name`.
+    pub fn get_connection_unix_user(&self, name: &str) -> Result<u32, Error> {
+        self.call("GetConnectionUnixUser", (name,)).map(|body| body.child_value(0).get_value())
+    }
+
+    /// Gets the unique name of the connection owning `name`.
+    pub fn get_name_owner(&self, name: &str) -> Result<String, Error> {
+        self.call("GetNameOwner", (name,)).map(|body| body.child_value(0).get_value())
+    }
+
+    /// Lists the names currently owned on the bus, unique names included.
+    pub fn list_names(&self) -> Result<Vec<String>, Error> {
+        self.call("ListNames", ()).map(|body| array_values(&body.child_value(0)))
+    }
+
+    /// Checks whether `name` has an owner.
+    pub fn name_has_owner(&self, name: &str) -> Result<bool, Error> {
+        self.call("NameHasOwner", (name,)).map(|body| body.child_value(0).get_value())
+    }
+
+    /// Reloads the configuration files of the message bus.
+    pub fn reload_config(&self) -> Result<(), Error> {
+        self.call("ReloadConfig", ()).map(|_| ())
+    }
+
+    /// Starts the service which can own `name`. `flags` is currently unused by the message bus and should be 0.
+    pub fn start_service_by_name(&self, name: &str, flags: u32) -> Result<StartServiceReply, Error> {
+        self.call("StartServiceByName", (name, flags))
+            .map(|body| {
+                if body.child_value(0).get_value::<u32>() == 2 {
+                    StartServiceReply::AlreadyRunning
+                }
+                else {
+                    StartServiceReply::Success
+                }
+            })
+    }
+
+    /// Connect the `NameAcquired` signal, sent to this connection when it acquires a name.
+    pub fn connect_name_acquired<F: Fn(&str) + 'static>(&self, callback: F) -> SignalSubscription {
+        self.subscribe("NameAcquired", move |parameters| {
+            let (name,): (String,) = FromVariant::from_variant(&parameters);
+            callback(&name);
+        })
+    }
+
+    /// Connect the `NameLost` signal, sent to this connection when it loses a name.
+    pub fn connect_name_lost<F: Fn(&str) + 'static>(&self, callback: F) -> SignalSubscription {
+        self.subscribe("NameLost", move |parameters| {
+            let (name,): (String,) = FromVariant::from_variant(&parameters);
+            callback(&name);
+        })
+    }
+
+    /// Connect the `NameOwnerChanged` signal, emitted when the owner of a name changes.
+    /// The callback receives the name, the old owner and the new owner, which are `None` when the name had or has no owner.
+    pub fn connect_name_owner_changed<F: Fn(&str, Option<&str>, Option<&str>) + 'static>(&self, callback: F) -> SignalSubscription {
+        self.subscribe("NameOwnerChanged", move |parameters| {
+            let (name, old_owner, new_owner): (String, String, String) = FromVariant::from_variant(&parameters);
+            callback(&name, optional_name(&old_owner), optional_name(&new_owner));
+        })
+    }
+
+    fn call<T: ToVariant>(&self, method_name: &str, parameters: T) -> Result<Variant, Error> {
+        let method_call_message = Message::new_method_call(DBUS_NAME, DBUS_PATH, DBUS_INTERFACE, method_name);
+        method_call_message.set_body(parameters);
+        self.connection.send_message_with_reply_sync(method_call_message, SEND_MESSAGE_FLAGS_NONE, -1)
+            .map(|message| message.get_body())
+    }
+
+    fn subscribe<F: Fn(Variant) + 'static>(&self, signal_name: &str, callback: F) -> SignalSubscription {
+        self.connection.signal_subscribe(Some(DBUS_NAME), Some(DBUS_INTERFACE), Some(signal_name), Some(DBUS_PATH), None, SIGNAL_FLAGS_NONE,
+            move |_, _, _, _, _, parameters| callback(parameters))
+    }
+}
+
+fn array_values<T: FromFFI + FromFormat>(array: &Variant) -> Vec<T> {
+    (0..array.n_children())
+        .map(|index| array.child_value(index).get_value())
+        .collect()
+}
+
+fn optional_name(name: &str) -> Option<&str> {
+    if name.is_empty() {
+        None
+    }
+    else {
+        Some(name)
+    }
+}
@@ -21,19 +21,26 @@
 
 //! Bus Connections
 
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
-use std::ptr::null_mut;
+use std::ptr::{null, null_mut};
+use std::rc::Rc;
 
-use gio_sys::{GAsyncResult, GDBusConnection, GDBusInterfaceGetPropertyFunc, GDBusInterfaceMethodCallFunc, GDBusInterfaceSetPropertyFunc, GDBusMessage, GDBusMethodInvocation, GDBusSendMessageFlags, g_dbus_connection_register_object, g_dbus_connection_send_message, g_dbus_connection_send_message_with_reply, g_dbus_connection_send_message_with_reply_finish, g_dbus_connection_send_message_with_reply_sync, g_dbus_message_get_message_type, g_dbus_message_to_gerror, G_DBUS_MESSAGE_TYPE_ERROR};
+use gio_sys::{GAsyncResult, GDBusConnection, GDBusConnectionFlags, GDBusInterfaceGetPropertyFunc, GDBusInterfaceMethodCallFunc, GDBusInterfaceSetPropertyFunc, GDBusMessage, GDBusMethodInvocation, GDBusSendMessageFlags, GDBusSignalFlags, g_bus_get_sync, g_dbus_connection_emit_signal, g_dbus_connection_new_for_address_sync, g_dbus_connection_register_object, g_dbus_connection_send_message, g_dbus_connection_send_message_with_reply, g_dbus_connection_send_message_with_reply_finish, g_dbus_connection_send_message_with_reply_sync, g_dbus_connection_signal_subscribe, g_dbus_connection_signal_unsubscribe, g_dbus_connection_unregister_object, g_dbus_message_get_message_type, g_dbus_error_quark, g_dbus_message_to_gerror, G_DBUS_ERROR_INVALID_ARGS, G_DBUS_MESSAGE_TYPE_ERROR};
 use glib::error::Error;
-use glib::translate::from_glib_full;
-use glib_sys::{GError, GVariant};
+use glib::translate::{ToGlib, from_glib_full};
+use glib_sys::{GError, GVariant, g_error_new_literal, g_variant_new_array, g_variant_new_dict_entry, g_variant_new_string, g_variant_new_strv, g_variant_new_tuple, g_variant_new_variant, g_variant_type_free, g_variant_type_new};
 use gobject_sys::GObject;
 use libc::{c_char, c_void};
 
+use future::{self, Reply};
+#[cfg(feature = "futures")]
+use future::{EventSender, EventStream};
+use interface::{EmitsChangedSignal, Interface, emits_changed_signal};
 use message::Message;
 use method_invocation::MethodInvocation;
-use node_info::InterfaceInfo;
+use node_info::{AnnotationInfo, InterfaceInfo, Iter};
+use own_name::Type;
 use variant::Variant;
 
 #[repr(C)]
@@ -44,7 +51,25 @@ struct GDBusInterfaceVTable {
 }
 
 bitflags! {
-    /// Flags used in `OwnName::new()`.
+    /// Flags used when creating a new `Connection`.
+    pub flags ConnectionFlags: u32 {
+        /// No flags set.
+        const CONNECTION_FLAGS_NONE = 0,
+        /// Perform authentication against server.
+        const CONNECTION_FLAGS_AUTHENTICATION_CLIENT = 1,
+        /// Perform authentication against client.
+        const CONNECTION_FLAGS_AUTHENTICATION_SERVER = 2,
+        /// When authenticating as a server, allow the anonymous authentication method.
+        const CONNECTION_FLAGS_AUTHENTICATION_ALLOW_ANONYMOUS = 4,
+        /// Pass this flag if connecting to a peer that is a message bus. This means that the `Hello()` method will be invoked as part of the connection setup.
+        const CONNECTION_FLAGS_MESSAGE_BUS_CONNECTION = 8,
+        /// If set, processing of D-Bus messages is delayed until `g_dbus_connection_start_message_processing()` is called.
+        const CONNECTION_FLAGS_DELAY_MESSAGE_PROCESSING = 16,
+    }
+}
+
+bitflags! {
+    /// Flags used in `Connection::send_message()`.
     pub flags SendMessageFlags: u32 {
         /// No flags set.
         const SEND_MESSAGE_FLAGS_NONE = 0,
@@ -53,7 +78,29 @@ bitflags! {
     }
 }
 
-type SendMessageCallback = Box<Box<Fn(Result<Message, Error>) + 'static>>;
+type SendMessageCallback = Box<Box<FnOnce(Result<Message, Error>) + 'static>>;
+
+type SignalCallback = Box<Box<Fn(&Connection, Option<&str>, &str, &str, &str, Variant) + 'static>>;
+
+struct ObjectCallbacks {
+    method_call: Box<Fn(&str, Variant, MethodInvocation)>,
+    get_property: Option<Box<Fn(&str) -> Option<Variant>>>,
+    set_property: Option<Box<Fn(&str, Variant) -> bool>>,
+}
+
+bitflags! {
+    /// Flags used when subscribing to signals via `Connection::signal_subscribe()`.
+    pub flags SignalFlags: u32 {
+        /// No flags set.
+        const SIGNAL_FLAGS_NONE = 0,
+        /// Don't actually send the AddMatch D-Bus call for this signal subscription. This gives you more control over which match rules you add (but you must add them manually).
+        const SIGNAL_FLAGS_NO_MATCH_RULE = 1,
+        /// Match first arguments that contain a bus or interface name with the given namespace.
+        const SIGNAL_FLAGS_MATCH_ARG0_NAMESPACE = 2,
+        /// Match first arguments that contain an object path that is either equivalent to the given path, or one of the paths is a subpath of the other.
+        const SIGNAL_FLAGS_MATCH_ARG0_PATH = 4,
+    }
+}
 
 /// The `GDBusConnection` type is used for D-Bus connections to remote peers such as a message buses. It is a low-level API that offers a lot of flexibility. For instance, it lets you establish a connection over any transport that can by represented as an `GIOStream`.
 #[derive(Clone)]
@@ -65,6 +112,32 @@ impl Connection {
         Connection(connection)
     }
 
+    /// Synchronously connects to the message bus specified by `bus_type`. Note that the returned object may shared with other callers, e.g. if two separate parts of a process calls this function with the same `bus_type`, they will share the same object.
+    pub fn get_sync(bus_type: Type) -> Result<Self, Error> {
+        let mut error = null_mut();
+        let connection = unsafe { g_bus_get_sync(bus_type.to_glib(), null_mut(), &mut error) };
+        if error.is_null() {
+            Ok(Connection(connection))
+        }
+        else {
+            Err(unsafe { from_glib_full(error) })
+        }
+    }
+
+    /// Synchronously connects and sets up a D-Bus client connection for exchanging D-Bus messages with an endpoint specified by `address` which must be in the D-Bus address format.
+    /// This constructor can only be used to initiate client-side connections. If you are connecting to a message bus, `flags` must contain `CONNECTION_FLAGS_AUTHENTICATION_CLIENT` and `CONNECTION_FLAGS_MESSAGE_BUS_CONNECTION`.
+    pub fn new_for_address_sync(address: &str, flags: ConnectionFlags) -> Result<Self, Error> {
+        let address = CString::new(address).unwrap();
+        let mut error = null_mut();
+        let connection = unsafe { g_dbus_connection_new_for_address_sync(address.as_ptr(), GDBusConnectionFlags::from_bits_truncate(flags.bits()), null_mut(), null_mut(), &mut error) };
+        if error.is_null() {
+            Ok(Connection(connection))
+        }
+        else {
+            Err(unsafe { from_glib_full(error) })
+        }
+    }
+
     /// Registers callbacks for exported objects at `object_path` with the D-Bus interface that is described in `interface_info` .
     /// Calls to functions in `vtable` (and `user_data_free_func`) will happen in the thread-default main context of the thread you are calling this method from.
     /// Note that all `GVariant` values passed to functions in `vtable` will match the signature given in `interface_info` - if a remote caller passes incorrect values, the `org.freedesktop.DBus.Error.InvalidArgs` is returned to the remote caller.
@@ -74,15 +147,126 @@ impl Connection {
     /// GDBus automatically implements the standard D-Bus interfaces org.freedesktop.DBus.Properties, org.freedesktop.DBus.Introspectable and org.freedesktop.Peer, so you don't have to implement those for the objects you export. You can implement org.freedesktop.DBus.Properties yourself, e.g. to handle getting and setting of properties asynchronously.
     /// Note that the reference count on `interface_info` will be incremented by 1 (unless allocated statically, e.g. if the reference count is -1, see `g_dbus_interface_info_ref()`) for as long as the object is exported. Also note that `vtable` will be copied.
     /// See this server for an example of how to use this method.
-    pub fn register_object<F: Fn(&str, Variant, &MethodInvocation) + 'static>(&self, object_path: &str, interface_info: InterfaceInfo, method_call_callback: F) {
+    /// Returns the registration id that can be used with `unregister_object()`.
+    pub fn register_object<F: Fn(&str, Variant, MethodInvocation) + 'static>(&self, object_path: &str, interface_info: InterfaceInfo, method_call_callback: F) -> Result<u32, Error> {
+        self.register(object_path, interface_info, ObjectCallbacks {
+            method_call: Box::new(method_call_callback),
+            get_property: None,
+            set_property: None,
+        })
+    }
+
+    /// Like `register_object()` but also handles the properties of `interface_info`.
+    /// `get_property_callback` returns the value of the property, or `None` if it cannot be read.
+    /// `set_property_callback` returns whether the value was set.
+    pub fn register_object_with_properties<F, G, S>(&self, object_path: &str, interface_info: InterfaceInfo, method_call_callback: F, get_property_callback: G, set_property_callback: S) -> Result<u32, Error>
+        where F: Fn(&str, Variant, MethodInvocation) + 'static,
+              G: Fn(&str) -> Option<Variant> + 'static,
+              S: Fn(&str, Variant) -> bool + 'static,
+    {
+        self.register(object_path, interface_info, ObjectCallbacks {
+            method_call: Box::new(method_call_callback),
+            get_property: Some(Box::new(get_property_callback)),
+            set_property: Some(Box::new(set_property_callback)),
+        })
+    }
+
+    /// Registers `object`, whose type was generated by the `#[interface]` attribute, at `object_path`.
+    /// `PropertiesChanged` is emitted when a method call or a property change modifies the readable properties, according to their `EmitsChangedSignal` annotation, after the reply of asynchronous methods.
+    pub fn register_interface<T: Interface>(&self, object_path: &str, object: &Rc<RefCell<T>>) -> Result<u32, Error> {
+        let interface_info = try!(T::interface_info());
+        let method_interface_info = interface_info.clone();
+        let set_interface_info = interface_info.clone();
+        let method_object = object.clone();
+        let get_object = object.clone();
+        let set_object = object.clone();
+        let set_connection = self.clone();
+        let set_object_path = object_path.to_string();
+        self.register_object_with_properties(object_path, interface_info,
+            move |method_name, args, invocation| {
+                let connection = invocation.connection();
+                let object_path = invocation.object_path();
+                let old_values = method_object.borrow().property_values();
+                let changed_object = method_object.clone();
+                let interface_info = method_interface_info.clone();
+                // Asynchronous methods can change the properties until they reply.
+                T::call_method(&method_object, method_name, args, invocation, move || {
+                    emit_changed_properties(&connection, &object_path, &interface_info, old_values, changed_object.borrow().property_values());
+                });
+            },
+            move |property_name| get_object.borrow().get_property(property_name),
+            move |property_name, value| {
+                let old_values = set_object.borrow().property_values();
+                let result = set_object.borrow_mut().set_property(property_name, value);
+                emit_changed_properties(&set_connection, &set_object_path, &set_interface_info, old_values, set_object.borrow().property_values());
+                result
+            })
+    }
+
+    fn register(&self, object_path: &str, interface_info: InterfaceInfo, callbacks: ObjectCallbacks) -> Result<u32, Error> {
         let object_path = CString::new(object_path).unwrap();
         let vtable = GDBusInterfaceVTable {
             method_call: Some(handle_method_call),
             get_property: Some(handle_get_property),
             set_property: Some(handle_set_property),
         };
-        let callback: Box<Box<Fn(&str, Variant, &MethodInvocation) + 'static>> = Box::new(Box::new(method_call_callback));
-        unsafe { g_dbus_connection_register_object(self.0, object_path.as_ptr(), interface_info.to_glib(), &vtable as *const _ as *const _, Box::into_raw(callback) as *mut _, None, null_mut()) };
+        let callbacks = Box::new(callbacks);
+        let mut error = null_mut();
+        let id = unsafe { g_dbus_connection_register_object(self.0, object_path.as_ptr(), interface_info.to_glib(), &vtable as *const _ as *const _, Box::into_raw(callbacks) as *mut _, Some(free_object_callbacks), &mut error) };
+        if error.is_null() {
+            Ok(id)
+        }
+        else {
+            Err(unsafe { from_glib_full(error) })
+        }
+    }
+
+    /// Unregisters an object.
+    /// Returns `true` if the object was unregistered, `false` otherwise.
+    pub fn unregister_object(&self, registration_id: u32) -> bool {
+        unsafe { g_dbus_connection_unregister_object(self.0, registration_id) != 0 }
+    }
+
+    /// Emits a signal.
+    /// If the parameters `GVariant` is floating, it is consumed.
+    /// This can only fail if `parameters` is not compatible with the D-Bus protocol.
+    pub fn emit_signal(&self, destination_bus_name: Option<&str>, object_path: &str, interface_name: &str, signal_name: &str, parameters: Variant) -> Result<(), Error> {
+        let destination_bus_name = destination_bus_name.map(|name| CString::new(name).unwrap());
+        let object_path = CString::new(object_path).unwrap();
+        let interface_name = CString::new(interface_name).unwrap();
+        let signal_name = CString::new(signal_name).unwrap();
+        let mut error = null_mut();
+        unsafe { g_dbus_connection_emit_signal(self.0, optional_ptr(&destination_bus_name), object_path.as_ptr(), interface_name.as_ptr(), signal_name.as_ptr(), parameters.to_glib(), &mut error) };
+        if error.is_null() {
+            Ok(())
+        }
+        else {
+            Err(unsafe { from_glib_full(error) })
+        }
+    }
+
+    /// Emits the `org.freedesktop.DBus.Properties.PropertiesChanged` signal for the object at `object_path`.
+    /// `changed_properties` contains the names and new values of the properties, while `invalidated_properties` contains the names of the properties whose value changed but is not sent.
+    pub fn emit_properties_changed(&self, object_path: &str, interface_name: &str, changed_properties: Vec<(String, Variant)>, invalidated_properties: &[&str]) -> Result<(), Error> {
+        let parameters =
+            unsafe {
+                let entries: Vec<_> = changed_properties.into_iter()
+                    .map(|(name, value)| {
+                        let name = CString::new(name).unwrap();
+                        g_variant_new_dict_entry(g_variant_new_string(name.as_ptr()), g_variant_new_variant(value.to_glib()))
+                    })
+                    .collect();
+                let entry_type = CString::new("{sv}").unwrap();
+                let entry_type = g_variant_type_new(entry_type.as_ptr());
+                let changed = g_variant_new_array(entry_type, entries.as_ptr(), entries.len());
+                g_variant_type_free(entry_type);
+                let invalidated: Vec<_> = invalidated_properties.iter().map(|name| CString::new(*name).unwrap()).collect();
+                let invalidated: Vec<_> = invalidated.iter().map(|name| name.as_ptr()).collect();
+                let interface_name = CString::new(interface_name).unwrap();
+                let children = [g_variant_new_string(interface_name.as_ptr()), changed, g_variant_new_strv(invalidated.as_ptr(), invalidated.len() as isize)];
+                g_variant_new_tuple(children.as_ptr(), children.len())
+            };
+        self.emit_signal(None, object_path, "org.freedesktop.DBus.Properties", "PropertiesChanged", Variant::new(parameters))
     }
 
     /// Asynchronously sends `message` to the peer represented by `connection`.
@@ -107,6 +291,45 @@ impl Connection {
         }
     }
 
+    /// Subscribes to signals on `connection` and invokes `callback` whenever the signal is received. Note that `callback` will be invoked in the thread-default main context of the thread you are calling this method from.
+    /// If `connection` is not a message bus connection, `sender` must be `None`.
+    /// If `sender` is a well-known name note that `callback` is invoked with the unique name for the owner of `sender`, not the well-known name as one would expect. This is because the message bus rewrites the name. As such, to avoid certain race conditions, users should be tracking the name owner of the well-known name and use that when processing the received signal.
+    /// The callback receives the connection, the sender, the object path, the interface name, the signal name and the parameters of the signal.
+    /// The subscription is removed when the returned `SignalSubscription` is dropped.
+    pub fn signal_subscribe<F>(&self, sender: Option<&str>, interface_name: Option<&str>, member: Option<&str>, object_path: Option<&str>, arg0: Option<&str>, flags: SignalFlags, callback: F) -> SignalSubscription
+        where F: Fn(&Connection, Option<&str>, &str, &str, &str, Variant) + 'static
+    {
+        let sender = sender.map(|sender| CString::new(sender).unwrap());
+        let interface_name = interface_name.map(|name| CString::new(name).unwrap());
+        let member = member.map(|member| CString::new(member).unwrap());
+        let object_path = object_path.map(|path| CString::new(path).unwrap());
+        let arg0 = arg0.map(|arg0| CString::new(arg0).unwrap());
+        let callback: SignalCallback = Box::new(Box::new(callback));
+        let id = unsafe { g_dbus_connection_signal_subscribe(self.0, optional_ptr(&sender), optional_ptr(&interface_name), optional_ptr(&member), optional_ptr(&object_path), optional_ptr(&arg0),
+            GDBusSignalFlags::from_bits_truncate(flags.bits()), Some(signal_callback), Box::into_raw(callback) as *mut _, Some(free_signal_callback)) };
+        SignalSubscription {
+            connection: self.clone(),
+            id: id,
+        }
+    }
+
+    /// Like `signal_subscribe()` but returns a stream of the received signals, to be polled with `future::spawn_local()`.
+    #[cfg(feature = "futures")]
+    pub fn signal_stream(&self, sender: Option<&str>, interface_name: Option<&str>, member: Option<&str>, object_path: Option<&str>, arg0: Option<&str>, flags: SignalFlags) -> SignalStream {
+        let event_sender = EventSender::new();
+        let signal_sender = event_sender.clone();
+        let subscription = self.signal_subscribe(sender, interface_name, member, object_path, arg0, flags, move |_, sender, object_path, interface_name, signal_name, parameters| {
+            signal_sender.send(Signal {
+                interface_name: interface_name.to_string(),
+                object_path: object_path.to_string(),
+                parameters: parameters,
+                sender: sender.map(str::to_string),
+                signal_name: signal_name.to_string(),
+            });
+        });
+        event_sender.stream(subscription)
+    }
+
     /// Asynchronously sends `message` to the peer represented by `connection`.
     /// Unless `flags` contain the `G_DBUS_SEND_MESSAGE_FLAGS_PRESERVE_SERIAL` flag, the serial number
     /// will be assigned by `connection` and set on `message` via `g_dbus_message_set_serial()`. If
@@ -124,22 +347,101 @@ impl Connection {
     /// `G_DBUS_SEND_MESSAGE_FLAGS_PRESERVE_SERIAL` flag.
     /// See this server and client for an example of how to use this low-level API to send and
     /// receive UNIX file descriptors.
-    pub fn send_message_with_reply<F: Fn(Result<Message, Error>) + 'static>(&self, message: Message, flags: SendMessageFlags, callback: F) {
+    pub fn send_message_with_reply<F: FnOnce(Result<Message, Error>) + 'static>(&self, message: Message, flags: SendMessageFlags, callback: F) {
         let callback: SendMessageCallback = Box::new(Box::new(callback));
         unsafe { g_dbus_connection_send_message_with_reply(self.0, message.to_glib(), GDBusSendMessageFlags::from_bits_truncate(flags.bits()), -1, null_mut(), null_mut(), Some(send_message_callback), Box::into_raw(callback) as *mut _) };
     }
 
+    /// Like `send_message_with_reply()` but returns a future resolving to the reply.
+    pub fn send_message_with_reply_future(&self, message: Message, flags: SendMessageFlags) -> Reply<Message> {
+        let (sender, reply) = future::channel();
+        self.send_message_with_reply(message, flags, move |result| sender.send(result));
+        reply
+    }
+
     /// Synchronously sends `message` to the peer represented by `connection` and blocks the calling thread until a reply is received or the timeout is reached. See `g_dbus_connection_send_message_with_reply()` for the asynchronous version of this method.
     /// Unless `flags` contain the `G_DBUS_SEND_MESSAGE_FLAGS_PRESERVE_SERIAL` flag, the serial number will be assigned by `connection` and set on `message` via `g_dbus_message_set_serial()`. If `out_serial` is not `NULL`, then the serial number used will be written to this location prior to submitting the message to the underlying transport.
     /// If `connection` is closed then the operation will fail with `G_IO_ERROR_CLOSED`. If `cancellable` is canceled, the operation will fail with `G_IO_ERROR_CANCELLED`. If `message` is not well-formed, the operation fails with `G_IO_ERROR_INVALID_ARGUMENT`.
     /// Note that `error` is only set if a local in-process error occurred. That is to say that the returned `GDBusMessage` object may be of type `G_DBUS_MESSAGE_TYPE_ERROR`. Use `g_dbus_message_to_gerror()` to transcode this to a `GError`.
     /// See this server and client for an example of how to use this low-level API to send and receive UNIX file descriptors.
     /// Note that `message` must be unlocked, unless `flags` contain the `G_DBUS_SEND_MESSAGE_FLAGS_PRESERVE_SERIAL` flag.
-    pub fn send_message_with_reply_sync(&self, message: Message, flags: SendMessageFlags) -> Result<Message, Error> {
+    /// `timeout_msec` is the timeout in milliseconds, -1 to use the default timeout or `i32::MAX` for no timeout.
+    pub fn send_message_with_reply_sync(&self, message: Message, flags: SendMessageFlags, timeout_msec: i32) -> Result<Message, Error> {
         let mut error = null_mut();
-        let message = unsafe { g_dbus_connection_send_message_with_reply_sync(self.0, message.to_glib(), GDBusSendMessageFlags::from_bits_truncate(flags.bits()), 100, null_mut(), null_mut(), &mut error) };
+        let message = unsafe { g_dbus_connection_send_message_with_reply_sync(self.0, message.to_glib(), GDBusSendMessageFlags::from_bits_truncate(flags.bits()), timeout_msec, null_mut(), null_mut(), &mut error) };
         message_to_result(message, error)
     }
+
+    /// Convert to the sys type.
+    pub fn to_glib(&self) -> *mut GDBusConnection {
+        self.0
+    }
+}
+
+/// A signal received by a `SignalStream`.
+#[cfg(feature = "futures")]
+pub struct Signal {
+    /// The interface name of the signal.
+    pub interface_name: String,
+    /// The object path the signal was emitted on.
+    pub object_path: String,
+    /// The parameters of the signal.
+    pub parameters: Variant,
+    /// The unique bus name of the sender of the signal, or `None` on a peer-to-peer connection.
+    pub sender: Option<String>,
+    /// The name of the signal.
+    pub signal_name: String,
+}
+
+/// Stream of signals, created by `Connection::signal_stream()`.
+#[cfg(feature = "futures")]
+pub type SignalStream = EventStream<Signal, SignalSubscription>;
+
+/// A subscription to a signal, created by `Connection::signal_subscribe()`.
+/// Unsubscribes from the signal when dropped.
+pub struct SignalSubscription {
+    connection: Connection,
+    id: u32,
+}
+
+impl Drop for SignalSubscription {
+    fn drop(&mut self) {
+        unsafe { g_dbus_connection_signal_unsubscribe(self.connection.0, self.id) };
+    }
+}
+
+/// Emit `PropertiesChanged` for the properties whose value changed, according to their `EmitsChangedSignal` annotation in `interface_info`.
+fn emit_changed_properties(connection: &Connection, object_path: &str, interface_info: &InterfaceInfo, old_values: Vec<(String, Variant)>, new_values: Vec<(String, Variant)>) {
+    let interface_annotations = annotation_values(interface_info.annotations());
+    let mut changed = vec![];
+    let mut invalidated = vec![];
+    for ((name, new_value), (_, old_value)) in new_values.into_iter().zip(old_values) {
+        if new_value == old_value {
+            continue;
+        }
+        let property_annotations = interface_info.lookup_property(&name)
+            .map(|property| annotation_values(property.annotations()))
+            .unwrap_or_default();
+        match emits_changed_signal(&property_annotations, &interface_annotations) {
+            EmitsChangedSignal::True => changed.push((name, new_value)),
+            EmitsChangedSignal::Invalidates => invalidated.push(name),
+            EmitsChangedSignal::Const | EmitsChangedSignal::False => (),
+        }
+    }
+    if !changed.is_empty() || !invalidated.is_empty() {
+        let invalidated: Vec<_> = invalidated.iter().map(String::as_str).collect();
+        connection.emit_properties_changed(object_path, &interface_info.name(), changed, &invalidated).ok();
+    }
+}
+
+fn annotation_values(annotations: Iter<AnnotationInfo>) -> Vec<(String, String)> {
+    annotations
+        .map(|annotation| (annotation.key(), annotation.value()))
+        .collect()
+}
+
+fn optional_ptr(string: &Option<CString>) -> *const c_char {
+    string.as_ref().map_or(null(), |string| string.as_ptr())
 }
 
 fn message_to_result(message: *mut GDBusMessage, mut error: *mut GError) -> Result<Message, Error> {
@@ -157,26 +459,67 @@ fn message_to_result(message: *mut GDBusMessage, mut error: *mut GError) -> Resu
     }
 }
 
+unsafe extern fn free_object_callbacks(user_data: *mut c_void) {
+    drop(Box::from_raw(user_data as *mut ObjectCallbacks));
+}
+
 unsafe extern fn handle_method_call(_connection: *mut GDBusConnection, _sender: *const c_char, _object_path: *const c_char, _interface_name: *const c_char, method_name: *const c_char, parameters: *mut GVariant, invocation: *mut GDBusMethodInvocation, user_data: *mut c_void) {
-    let callback: &Box<Fn(&str, Variant, &MethodInvocation) + 'static> = &*(user_data as *const Box<_>);
+    let callbacks = &*(user_data as *const ObjectCallbacks);
     let cstring = CStr::from_ptr(method_name);
-    callback(cstring.to_str().unwrap(), Variant::new(parameters), &MethodInvocation::new(invocation));
+    (callbacks.method_call)(cstring.to_str().unwrap(), Variant::new(parameters), MethodInvocation::new(invocation));
+}
+
+unsafe extern fn handle_get_property(_connection: *mut GDBusConnection, _sender: *const c_char, _object_path: *const c_char, _interface_name: *const c_char, property_name: *const c_char, error: *mut *mut GError, user_data: *mut c_void) -> *mut GVariant {
+    let callbacks = &*(user_data as *const ObjectCallbacks);
+    let property_name = CStr::from_ptr(property_name).to_str().unwrap();
+    match callbacks.get_property.as_ref().and_then(|callback| callback(property_name)) {
+        Some(value) => value.to_glib(),
+        None => {
+            *error = property_error(property_name, "cannot be read");
+            null_mut()
+        },
+    }
+}
+
+unsafe extern fn handle_set_property(_connection: *mut GDBusConnection, _sender: *const c_char, _object_path: *const c_char, _interface_name: *const c_char, property_name: *const c_char, value: *mut GVariant, error: *mut *mut GError, user_data: *mut c_void) -> i32 {
+    let callbacks = &*(user_data as *const ObjectCallbacks);
+    let property_name = CStr::from_ptr(property_name).to_str().unwrap();
+    if callbacks.set_property.as_ref().map_or(false, |callback| callback(property_name, Variant::new(value))) {
+        1
+    }
+    else {
+        *error = property_error(property_name, "cannot be written");
+        0
+    }
+}
+
+unsafe fn property_error(property_name: &str, reason: &str) -> *mut GError {
+    let message = CString::new(format!("Property '{}' {}", property_name, reason)).unwrap();
+    g_error_new_literal(g_dbus_error_quark(), G_DBUS_ERROR_INVALID_ARGS as i32, message.as_ptr())
 }
 
-unsafe extern fn handle_get_property(_connection: *mut GDBusConnection, _sender: *const c_char, _object_path: *const c_char, _interface_name: *const c_char, _property_name: *const c_char, _error: *mut *mut GError, _user_data: *mut c_void) -> *mut GVariant {
-    // TODO
-    null_mut()
+unsafe extern fn free_signal_callback(user_data: *mut c_void) {
+    drop(Box::from_raw(user_data as *mut Box<Fn(&Connection, Option<&str>, &str, &str, &str, Variant) + 'static>));
 }
 
-unsafe extern fn handle_set_property(_connection: *mut GDBusConnection, _sender: *const c_char, _object_path: *const c_char, _interface_name: *const c_char, _property_name: *const c_char, _value: *mut GVariant, _error: *mut *mut GError, _user_data: *mut c_void) -> i32 {
-    // TODO
-    0
+unsafe extern fn signal_callback(connection: *mut GDBusConnection, sender_name: *const c_char, object_path: *const c_char, interface_name: *const c_char, signal_name: *const c_char, parameters: *mut GVariant, user_data: *mut c_void) {
+    let callback: &Box<Fn(&Connection, Option<&str>, &str, &str, &str, Variant) + 'static> = &*(user_data as *const Box<_>);
+    let sender_name =
+        if sender_name.is_null() {
+            None
+        }
+        else {
+            Some(CStr::from_ptr(sender_name).to_str().unwrap())
+        };
+    callback(&Connection::new(connection), sender_name, CStr::from_ptr(object_path).to_str().unwrap(), CStr::from_ptr(interface_name).to_str().unwrap(),
+        CStr::from_ptr(signal_name).to_str().unwrap(), Variant::new(parameters));
 }
 
 unsafe extern fn send_message_callback(source_object: *mut GObject, res: *mut GAsyncResult, user_data: *mut c_void) {
     let mut error = null_mut();
     let message = g_dbus_connection_send_message_with_reply_finish(source_object as *mut _, res, &mut error);
     let result = message_to_result(message, error);
-    let callback: &Box<Fn(Result<Message, Error>) + 'static> = &*(user_data as *const Box<_>);
+    // The callback is only invoked once, so it can be freed here.
+    let callback: SendMessageCallback = Box::from_raw(user_data as *mut Box<_>);
     callback(result);
 }
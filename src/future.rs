@@ -0,0 +1,282 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Futures for asynchronous D-Bus calls.
+//!
+//! Futures and streams are completed from callbacks invoked in the thread-default main context, so they must be polled from the thread running that context.
+//! `spawn_local()` is the executor for this: it polls the future from idle sources attached to the thread-default main context of the calling thread, so the future runs while this context is iterated, e.g. in `gtk::main()` for the global default context.
+//! The streams are only available with the `futures` feature.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+#[cfg(feature = "futures")]
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+use glib::Error;
+use glib_sys::{GFALSE, GMainContext, gboolean, g_idle_source_new, g_main_context_ref_thread_default, g_main_context_unref, g_source_attach, g_source_set_callback, g_source_unref};
+use libc::c_void;
+
+struct Task {
+    /// The main context in which the task was spawned.
+    context: MainContext,
+    /// The future, `None` while it is polled.
+    future: Option<Pin<Box<Future<Output = ()>>>>,
+}
+
+/// A reference on a `GMainContext`.
+struct MainContext(*mut GMainContext);
+
+impl Drop for MainContext {
+    fn drop(&mut self) {
+        unsafe { g_main_context_unref(self.0) };
+    }
+}
+
+thread_local! {
+    static NEXT_TASK_ID: Cell<usize> = Cell::new(1);
+    static TASKS: RefCell<HashMap<usize, Task>> = RefCell::new(HashMap::new());
+}
+
+const TASK_WAKER_VTABLE: RawWakerVTable = RawWakerVTable::new(clone_task_waker, wake_task, wake_task, drop_task_waker);
+
+/// Run `future` to completion in the thread-default main context of the calling thread.
+/// The future is polled from an idle source attached to this context every time it is woken up, so it only runs while this context is iterated.
+pub fn spawn_local<F: Future<Output = ()> + 'static>(future: F) {
+    let id = NEXT_TASK_ID.with(|next_id| {
+        let id = next_id.get();
+        next_id.set(id + 1);
+        id
+    });
+    let task = Task {
+        context: MainContext(unsafe { g_main_context_ref_thread_default() }),
+        future: Some(Box::pin(future)),
+    };
+    TASKS.with(|tasks| tasks.borrow_mut().insert(id, task));
+    schedule_task(id);
+}
+
+fn schedule_task(id: usize) {
+    TASKS.with(|tasks| {
+        if let Some(task) = tasks.borrow().get(&id) {
+            unsafe {
+                let source = g_idle_source_new();
+                g_source_set_callback(source, Some(poll_task), id as *mut c_void, None);
+                g_source_attach(source, task.context.0);
+                g_source_unref(source);
+            }
+        }
+    });
+}
+
+unsafe extern "C" fn poll_task(data: *mut c_void) -> gboolean {
+    let id = data as usize;
+    // The future is taken out of the task while it is polled, so that it can spawn other tasks.
+    let future = TASKS.with(|tasks| tasks.borrow_mut().get_mut(&id).and_then(|task| task.future.take()));
+    if let Some(mut future) = future {
+        let waker = Waker::from_raw(RawWaker::new(data as *const (), &TASK_WAKER_VTABLE));
+        let mut context = Context::from_waker(&waker);
+        if future.as_mut().poll(&mut context).is_pending() {
+            TASKS.with(|tasks| {
+                if let Some(task) = tasks.borrow_mut().get_mut(&id) {
+                    task.future = Some(future);
+                }
+            });
+        }
+        else {
+            TASKS.with(|tasks| tasks.borrow_mut().remove(&id));
+        }
+    }
+    GFALSE
+}
+
+unsafe fn clone_task_waker(data: *const ()) -> RawWaker {
+    RawWaker::new(data, &TASK_WAKER_VTABLE)
+}
+
+unsafe fn wake_task(data: *const ()) {
+    schedule_task(data as usize);
+}
+
+unsafe fn drop_task_waker(_data: *const ()) {
+}
+
+/// Boxed future, to return from a `dbus_class!` method which replies asynchronously.
+pub struct Async<T>(Pin<Box<Future<Output = T>>>);
+
+impl<T> Async<T> {
+    /// Box `future`.
+    pub fn new<F: Future<Output = T> + 'static>(future: F) -> Self {
+        Async(Box::pin(future))
+    }
+}
+
+impl<T> Future for Async<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<T> {
+        self.0.as_mut().poll(context)
+    }
+}
+
+struct Shared<T> {
+    result: Option<Result<T, Error>>,
+    waker: Option<Waker>,
+}
+
+/// Create a `Reply` future with the `ReplySender` used to complete it.
+pub fn channel<T>() -> (ReplySender<T>, Reply<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        result: None,
+        waker: None,
+    }));
+    (ReplySender(shared.clone()), Reply(shared))
+}
+
+/// Future resolving to the result of an asynchronous call.
+/// The result is sent from a callback invoked in the thread-default main context, so the future must be polled from the same thread, e.g. with `spawn_local()`.
+pub struct Reply<T>(Rc<RefCell<Shared<T>>>);
+
+impl<T> Future for Reply<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        let mut shared = self.0.borrow_mut();
+        match shared.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                shared.waker = Some(context.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+/// Sending side of a `Reply`.
+pub struct ReplySender<T>(Rc<RefCell<Shared<T>>>);
+
+impl<T> ReplySender<T> {
+    /// Complete the `Reply` future with `result`.
+    pub fn send(&self, result: Result<T, Error>) {
+        let waker = {
+            let mut shared = self.0.borrow_mut();
+            shared.result = Some(result);
+            shared.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+struct Queue<T> {
+    events: VecDeque<T>,
+    waker: Option<Waker>,
+}
+
+/// Stream of the events emitted by `handle`, which is kept alive by the stream.
+/// Poll it from a future run by `spawn_local()`.
+/// The stream never ends: drop it to stop receiving the events.
+#[cfg(feature = "futures")]
+pub struct EventStream<T, H> {
+    handle: H,
+    queue: Rc<RefCell<Queue<T>>>,
+}
+
+#[cfg(feature = "futures")]
+impl<T, H> EventStream<T, H> {
+    /// Get the handle producing the events.
+    pub fn handle(&self) -> &H {
+        &self.handle
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T, H> Stream for EventStream<T, H> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<T>> {
+        let mut queue = self.queue.borrow_mut();
+        match queue.events.pop_front() {
+            Some(event) => Poll::Ready(Some(event)),
+            None => {
+                queue.waker = Some(context.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+/// Sending side of an `EventStream`.
+#[cfg(feature = "futures")]
+pub struct EventSender<T>(Rc<RefCell<Queue<T>>>);
+
+#[cfg(feature = "futures")]
+impl<T> Clone for EventSender<T> {
+    fn clone(&self) -> Self {
+        EventSender(self.0.clone())
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> Default for EventSender<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T> EventSender<T> {
+    /// Create a new sender.
+    pub fn new() -> Self {
+        EventSender(Rc::new(RefCell::new(Queue {
+            events: VecDeque::new(),
+            waker: None,
+        })))
+    }
+
+    /// Create the `EventStream` receiving the events sent by this sender.
+    /// `handle` is the value that must stay alive while events are produced.
+    pub fn stream<H>(&self, handle: H) -> EventStream<T, H> {
+        EventStream {
+            handle: handle,
+            queue: self.0.clone(),
+        }
+    }
+
+    /// Push `event` to the `EventStream`.
+    pub fn send(&self, event: T) {
+        let waker = {
+            let mut queue = self.0.borrow_mut();
+            queue.events.push_back(event);
+            queue.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
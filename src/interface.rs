@@ -0,0 +1,152 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Objects implementing a D-Bus interface, generated by the `#[interface]` attribute.
+//!
+//! ```ignore
+//! struct Counter {
+//!     count: i64,
+//! }
+//!
+//! #[gdbus::interface("org.example.Counter")]
+//! impl Counter {
+//!     #[property]
+//!     fn count(&self) -> i64 {
+//!         self.count
+//!     }
+//!
+//!     fn increment(&mut self, step: i64) {
+//!         self.count += step;
+//!     }
+//! }
+//!
+//! let counter = Rc::new(RefCell::new(Counter { count: 0 }));
+//! connection.register_interface("/org/example/Counter", &counter)?;
+//! ```
+//!
+//! Every method of the impl block is exported, except the getters (`fn name(&self) -> T`) and setters (`fn set_name(&mut self, value: T)`) marked with `#[property]`.
+//! Methods and properties can be annotated with `#[annotation("name" = "value")]`, and renamed on the bus with `#[dbus_name("Name")]`.
+//!
+//! The proxies generated by `dbus_interface!` and `#[proxy]` implement `InterfaceProxy`, so that they can be created by generic code like `ObjectManagerClient::proxy()`.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use glib::Error;
+
+use connection::Connection;
+use method_invocation::MethodInvocation;
+use node_info::{InterfaceInfo, MethodInfo, MethodInfoBuilder, PropertyInfo, PropertyInfoBuilder, PropertyInfoFlags};
+use variant::Variant;
+
+/// How `PropertiesChanged` reports a change of a property, from its `org.freedesktop.DBus.Property.EmitsChangedSignal` annotation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EmitsChangedSignal {
+    /// The new value is sent in the signal (the default).
+    True,
+    /// The property is listed as invalidated, without its value.
+    Invalidates,
+    /// The property never changes: the signal is not emitted.
+    Const,
+    /// The signal is not emitted.
+    False,
+}
+
+/// Get how the changes of a property are reported, from its annotations, defaulting to the annotations of its interface.
+pub fn emits_changed_signal<S: AsRef<str>>(property_annotations: &[(S, S)], interface_annotations: &[(S, S)]) -> EmitsChangedSignal {
+    let value = property_annotations.iter()
+        .chain(interface_annotations)
+        .find(|&&(ref name, _)| name.as_ref() == "org.freedesktop.DBus.Property.EmitsChangedSignal")
+        .map(|&(_, ref value)| value.as_ref());
+    match value {
+        Some("invalidates") => EmitsChangedSignal::Invalidates,
+        Some("const") => EmitsChangedSignal::Const,
+        Some("false") => EmitsChangedSignal::False,
+        _ => EmitsChangedSignal::True,
+    }
+}
+
+/// Trait for the types exported on a connection with `Connection::register_interface()`.
+pub trait Interface: 'static {
+    /// The D-Bus name of the interface.
+    const INTERFACE_NAME: &'static str;
+
+    /// Build the introspection data of the interface.
+    fn interface_info() -> Result<InterfaceInfo, Error> where Self: Sized;
+
+    /// Call the method `method_name` of `object`, reply to `invocation` and then call `finished`.
+    fn call_method<F: FnOnce() + 'static>(object: &Rc<RefCell<Self>>, method_name: &str, args: Variant, invocation: MethodInvocation, finished: F) where Self: Sized;
+
+    /// Get the value of a readable property, or `None` if there is no such property.
+    fn get_property(&self, property_name: &str) -> Option<Variant>;
+
+    /// Set the value of a writable property and return whether there is such a property.
+    fn set_property(&mut self, property_name: &str, value: Variant) -> bool;
+
+    /// Get the values of the readable properties, used to emit `PropertiesChanged`.
+    fn property_values(&self) -> Vec<(String, Variant)>;
+}
+
+/// Trait for the proxies generated by `dbus_interface!` and the `#[proxy]` attribute.
+pub trait InterfaceProxy {
+    /// The D-Bus name of the proxied interface.
+    const INTERFACE_NAME: &'static str;
+
+    /// Create a proxy for the object at `object_path` owned by `dbus_name`.
+    fn new_for_connection(connection: &Connection, dbus_name: &str, object_path: &str) -> Self;
+}
+
+#[doc(hidden)]
+pub fn method_info(name: &str, in_args: &[(&str, &str)], out_signatures: Vec<&str>, annotations: &[(&str, &str)]) -> MethodInfoBuilder {
+    let mut method = MethodInfo::builder(name);
+    for &(arg_name, signature) in in_args {
+        method = method.in_arg(arg_name, dbus_signature(signature));
+    }
+    let single = out_signatures.len() == 1;
+    for (index, signature) in out_signatures.into_iter().enumerate() {
+        let arg_name =
+            if single {
+                "result".to_string()
+            }
+            else {
+                format!("result{}", index)
+            };
+        method = method.out_arg(&arg_name, dbus_signature(signature));
+    }
+    for &(annotation_name, value) in annotations {
+        method = method.annotation(annotation_name, value);
+    }
+    method
+}
+
+#[doc(hidden)]
+pub fn property_info(name: &str, signature: &str, flags: PropertyInfoFlags, annotations: &[(&str, &str)]) -> PropertyInfoBuilder {
+    let mut property = PropertyInfo::builder(name, dbus_signature(signature), flags);
+    for &(annotation_name, value) in annotations {
+        property = property.annotation(annotation_name, value);
+    }
+    property
+}
+
+/// Get the D-Bus signature of a `ToFormat` format string, e.g. `as` for `@as`.
+fn dbus_signature(format: &str) -> &str {
+    format.trim_start_matches('@')
+}
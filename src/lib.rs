@@ -29,17 +29,31 @@
 
 #[macro_use]
 extern crate bitflags;
+#[cfg(feature = "futures")]
+extern crate futures_core;
+extern crate gdbus_macros;
 extern crate gio_sys;
 extern crate glib;
 extern crate glib_sys;
 extern crate gobject_sys;
 extern crate libc;
 
+pub mod bus;
 pub mod connection;
+pub mod future;
+pub mod interface;
 pub mod message;
 pub mod macros;
 pub mod method_invocation;
 pub mod node_info;
+pub mod object_manager;
 pub mod own_name;
+pub mod proxy;
 pub mod variant;
 pub mod watch;
+
+pub use gdbus_macros::{interface, proxy};
+/// Error type of the `gdbus` functions, re-exported so that the generated code does not require the `glib` crate.
+pub use glib::Error;
+#[doc(hidden)]
+pub use gdbus_macros::dbus_proxy_properties as __dbus_proxy_properties;
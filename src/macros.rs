@@ -20,6 +20,9 @@
  */
 
 //! Convenient macros to create `DBus` client and server.
+//!
+//! The `#[interface]` and `#[proxy]` attributes are a lower-level alternative to `dbus_class!` and `dbus_interface!`: they accept any method syntax and report errors at the offending item.
+//! Unlike `dbus_class!`, `#[interface]` does not own a name nor manage the objects: register the type with `Connection::register_interface()` and own the name with `own_name::OwnName`.
 
 #[macro_export]
 macro_rules! replace_expr {
@@ -43,10 +46,53 @@ macro_rules! c_stringify {
 #[macro_export]
 macro_rules! dbus_prototypes {
     ($interface_name:expr,) => {};
+    ($interface_name:expr, fn $func_name:ident $args:tt -> ($($return_type:ty),*), $async_name:ident $(, $future_name:ident)* ; $($rest:tt)*) => {
+        dbus_async_prototype!($interface_name, $func_name, $async_name $(, $future_name)*, $args -> ($($return_type),*));
+        dbus_prototypes!($interface_name, fn $func_name $args -> ($($return_type),*); $($rest)*);
+    };
+    ($interface_name:expr, fn $func_name:ident $args:tt -> $return_type:ty, $async_name:ident $(, $future_name:ident)* ; $($rest:tt)*) => {
+        dbus_async_prototype!($interface_name, $func_name, $async_name $(, $future_name)*, $args -> $return_type);
+        dbus_prototypes!($interface_name, fn $func_name $args -> $return_type; $($rest)*);
+    };
+    ($interface_name:expr, fn $func_name:ident $args:tt, $async_name:ident $(, $future_name:ident)* ; $($rest:tt)*) => {
+        dbus_async_prototype!($interface_name, $func_name, $async_name $(, $future_name)*, $args);
+        dbus_prototypes!($interface_name, fn $func_name $args; $($rest)*);
+    };
+    ($interface_name:expr, signal $name:ident ($($arg:ident : $arg_type:ty),*), $connect:ident ; $($rest:tt)*) => {
+        pub fn $connect<F: Fn($($arg_type),*) + 'static>(&self, callback: F) -> ::gdbus::connection::SignalSubscription {
+            self.connection.signal_subscribe(Some(&self.dbus_name), Some($interface_name), Some(stringify!($name)), Some(&self.object_path), None, ::gdbus::connection::SIGNAL_FLAGS_NONE,
+                move |_, _, _, _, _, parameters| {
+                    let ($($arg,)*): ($($arg_type,)*) = ::gdbus::variant::FromVariant::from_variant(&parameters);
+                    callback($($arg),*);
+                })
+        }
+        dbus_prototypes!($interface_name, $($rest)*);
+    };
+    ($interface_name:expr, property $name:ident : $property_type:ty ; $($rest:tt)*) => {
+        pub fn $name(&self) -> Result<$property_type, ::gdbus::Error> {
+            let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, "org.freedesktop.DBus.Properties", "Get");
+            method_call_message.set_body(($interface_name, stringify!($name)));
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
+                .map(|message| {
+                    let (value,): (::gdbus::variant::Variant,) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
+                    value.get_value()
+                })
+        }
+        dbus_prototypes!($interface_name, $($rest)*);
+    };
+    ($interface_name:expr, property $name:ident : $property_type:ty, $setter:ident ; $($rest:tt)*) => {
+        pub fn $setter(&self, value: $property_type) -> Result<(), ::gdbus::Error> {
+            let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, "org.freedesktop.DBus.Properties", "Set");
+            method_call_message.set_body(($interface_name, stringify!($name), ::gdbus::variant::Variant::from_value(&value)));
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
+                .map(|_| ())
+        }
+        dbus_prototypes!($interface_name, property $name : $property_type; $($rest)*);
+    };
     ($interface_name:expr, fn $func_name:ident (& $(mut)* self) -> ( $($return_type:ty),* ) ; $($rest:tt)* ) => {
-        pub fn $func_name(&self) -> Result<($($return_type),*), ::glib::error::Error> {
+        pub fn $func_name(&self) -> Result<($($return_type),*), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let response: ($($return_type),*) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -55,9 +101,9 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident (& $(mut)* self) -> $return_type:ty ; $($rest:tt)* ) => {
-        pub fn $func_name(&self) -> Result<$return_type, ::glib::error::Error> {
+        pub fn $func_name(&self) -> Result<$return_type, ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let (response,): ($return_type,) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -66,17 +112,17 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident (& $(mut)* self) ; $($rest:tt)* ) => {
-        pub fn $func_name(&self) -> Result<(), ::glib::error::Error> {
+        pub fn $func_name(&self) -> Result<(), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             self.connection.send_message(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
         }
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident (& $(mut)* self, $($arg:ident : $($arg_type:tt)*),*) -> ( $($return_type:ty),* ) ; $($rest:tt)* ) => {
-        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<($($return_type),*), ::glib::error::Error> {
+        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<($($return_type),*), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             method_call_message.set_body(($($arg,)*));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let response: ($($return_type),*) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -85,10 +131,10 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident (& $(mut)* self, $($arg:ident : $($arg_type:tt)*),*) -> $return_type:ty ; $($rest:tt)* ) => {
-        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<$return_type, ::glib::error::Error> {
+        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<$return_type, ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             method_call_message.set_body(($($arg,)*));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let (response,): ($return_type,) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -97,7 +143,7 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident (& $(mut)* self, $($arg:ident : $($arg_type:tt)*),*) ; $($rest:tt)* ) => {
-        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<(), ::glib::error::Error> {
+        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<(), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             method_call_message.set_body(($($arg,)*));
             self.connection.send_message(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
@@ -105,9 +151,9 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident () -> ( $($return_type:ty),* ) ; $($rest:tt)* ) => {
-        pub fn $func_name(&self) -> Result<($($return_type),*), ::glib::error::Error> {
+        pub fn $func_name(&self) -> Result<($($return_type),*), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let response: ($($return_type),*) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -116,9 +162,9 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident () -> $return_type:ty ; $($rest:tt)* ) => {
-        pub fn $func_name(&self) -> Result<$return_type, ::glib::error::Error> {
+        pub fn $func_name(&self) -> Result<$return_type, ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let (response,): ($return_type,) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -127,17 +173,17 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident () ; $($rest:tt)* ) => {
-        pub fn $func_name(&self) -> Result<(), ::glib::error::Error> {
+        pub fn $func_name(&self) -> Result<(), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             self.connection.send_message(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
         }
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) -> ( $($return_type:ty),* ) ; $($rest:tt)* ) => {
-        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<($($return_type),*), ::glib::error::Error> {
+        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<($($return_type),*), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             method_call_message.set_body(($($arg,)*));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let response: ($($return_type),*) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -146,10 +192,10 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) -> $return_type:ty ; $($rest:tt)* ) => {
-        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<$return_type, ::glib::error::Error> {
+        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<$return_type, ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             method_call_message.set_body(($($arg,)*));
-            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
+            self.connection.send_message_with_reply_sync(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, 100)
                 .map(|message| {
                     let (response,): ($return_type,) = ::gdbus::variant::FromVariant::from_variant(&message.get_body());
                     response
@@ -158,7 +204,7 @@ macro_rules! dbus_prototypes {
         dbus_prototypes!($interface_name, $($rest)*);
     };
     ($interface_name:expr, fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) ; $($rest:tt)* ) => {
-        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<(), ::glib::error::Error> {
+        pub fn $func_name(&self, $($arg : $($arg_type)*),*) -> Result<(), ::gdbus::Error> {
             let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
             method_call_message.set_body(($($arg,)*));
             self.connection.send_message(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE)
@@ -177,14 +223,18 @@ macro_rules! dbus_interface {
         }
 
         impl $class_name {
-            pub fn new(dbus_name: &str, object_path: &str) -> Self {
-                let connection =
-                    unsafe {
-                        ::gio_sys::g_bus_get_sync(::gio_sys::G_BUS_TYPE_SESSION, ::std::ptr::null_mut(), ::std::ptr::null_mut())
-                    };
-                let connection = ::gdbus::connection::Connection::new(connection);
+            pub fn new(dbus_name: &str, object_path: &str) -> Result<Self, ::gdbus::Error> {
+                $class_name::new_for_bus(::gdbus::own_name::Type::Session, dbus_name, object_path)
+            }
+
+            pub fn new_for_bus(bus_type: ::gdbus::own_name::Type, dbus_name: &str, object_path: &str) -> Result<Self, ::gdbus::Error> {
+                let connection = try!(::gdbus::connection::Connection::get_sync(bus_type));
+                Ok($class_name::new_for_connection(&connection, dbus_name, object_path))
+            }
+
+            pub fn new_for_connection(connection: &::gdbus::connection::Connection, dbus_name: &str, object_path: &str) -> Self {
                 $class_name {
-                    connection: connection,
+                    connection: connection.clone(),
                     dbus_name: dbus_name.to_string(),
                     object_path: object_path.to_string(),
                 }
@@ -192,127 +242,142 @@ macro_rules! dbus_interface {
 
             dbus_prototypes!($interface_name, $($prototypes)+);
         }
+
+        impl ::gdbus::interface::InterfaceProxy for $class_name {
+            const INTERFACE_NAME: &'static str = $interface_name;
+
+            fn new_for_connection(connection: &::gdbus::connection::Connection, dbus_name: &str, object_path: &str) -> Self {
+                $class_name::new_for_connection(connection, dbus_name, object_path)
+            }
+        }
+
+        dbus_proxy_properties!($class_name, $interface_name, $($prototypes)+);
     };
 }
 
 #[macro_export]
-macro_rules! dbus_functions {
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr,) => {
+macro_rules! dbus_async_prototype {
+    ($interface_name:expr, $func_name:ident, $async_name:ident $(, $future_name:ident)*, (& $(mut)* self) $(-> $return_type:tt)*) => {
+        dbus_async_prototype!($interface_name, $func_name, $async_name $(, $future_name)*, () $(-> $return_type)*);
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident (& $(mut)* self) -> () $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            let this = &mut *$object.borrow_mut();
-            this.$func_name();
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
-        }
+    ($interface_name:expr, $func_name:ident, $async_name:ident $(, $future_name:ident)*, (& $(mut)* self, $($arg:ident : $($arg_type:tt)*),*) $(-> $return_type:tt)*) => {
+        dbus_async_prototype!($interface_name, $func_name, $async_name $(, $future_name)*, ($($arg : $($arg_type)*),*) $(-> $return_type)*);
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident (& $(mut)* self) -> ($($return_type:ty),*) $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            let this = &mut *$object.borrow_mut();
-            let result = this.$func_name();
-            $invocation.return_value(result);
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
+    ($interface_name:expr, $func_name:ident, $async_name:ident, ($($arg:ident : $($arg_type:tt)*),*) $(-> $return_type:tt)*) => {
+        pub fn $async_name<F: FnOnce(Result<dbus_return_type!($($return_type)*), ::gdbus::Error>) + 'static>(&self, $($arg : $($arg_type)*,)* callback: F) {
+            let method_call_message = ::gdbus::message::Message::new_method_call(&self.dbus_name, &self.object_path, $interface_name, stringify!($func_name));
+            method_call_message.set_body(($($arg,)*));
+            self.connection.send_message_with_reply(method_call_message, ::gdbus::connection::SEND_MESSAGE_FLAGS_NONE, move |result| {
+                callback(result.map(|_message| dbus_decode_reply!(_message $(, $return_type)*)))
+            });
         }
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident (& $(mut)* self) -> $return_type:ty $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            let this = &mut *$object.borrow_mut();
-            let result = this.$func_name();
-            $invocation.return_value((result,));
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
+    ($interface_name:expr, $func_name:ident, $async_name:ident, $future_name:ident, ($($arg:ident : $($arg_type:tt)*),*) $(-> $return_type:tt)*) => {
+        dbus_async_prototype!($interface_name, $func_name, $async_name, ($($arg : $($arg_type)*),*) $(-> $return_type)*);
+
+        pub fn $future_name(&self, $($arg : $($arg_type)*),*) -> ::gdbus::future::Reply<dbus_return_type!($($return_type)*)> {
+            let (sender, reply) = ::gdbus::future::channel();
+            self.$async_name($($arg,)* move |result| sender.send(result));
+            reply
         }
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident (& $(mut)* self, $($arg:ident : $arg_type:ty),*) -> () $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            let this = &mut *$object.borrow_mut();
-            let ($($arg,)*): ($($arg_type,)*) = ::gdbus::variant::FromVariant::from_variant(&$args);
-            this.$func_name($($arg,)*);
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
-        }
+}
+
+#[macro_export]
+macro_rules! dbus_return_type {
+    () => { () };
+    ($return_type:ty) => { $return_type };
+}
+
+#[macro_export]
+macro_rules! dbus_decode_reply {
+    ($message:expr) => {
+        ()
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident (& $(mut)* self, $($arg:ident : $arg_type:ty),*) -> ($($return_type:ty),*) $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            let this = &mut *$object.borrow_mut();
-            let ($($arg,)*): ($($arg_type,)*) = ::gdbus::variant::FromVariant::from_variant(&$args);
-            let result = this.$func_name($($arg,)*);
-            $invocation.return_value(result);
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
-        }
+    ($message:expr, ($($return_type:ty),*)) => {{
+        let response: ($($return_type),*) = ::gdbus::variant::FromVariant::from_variant(&$message.get_body());
+        response
+    }};
+    ($message:expr, $return_type:ty) => {{
+        let (response,): ($return_type,) = ::gdbus::variant::FromVariant::from_variant(&$message.get_body());
+        response
+    }};
+}
+
+#[macro_export]
+macro_rules! dbus_proxy_properties {
+    ($class_name:ident, $interface_name:expr, @properties [$($names:ident : $types:tt),*]) => {
+        ::gdbus::__dbus_proxy_properties!($class_name, $interface_name, [$($names : $types),*]);
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident (& $(mut)* self, $($arg:ident : $arg_type:ty),*) -> $return_type:ty $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            let this = &mut *$object.borrow_mut();
-            let ($($arg,)*): ($($arg_type,)*) = ::gdbus::variant::FromVariant::from_variant(&$args);
-            let result = this.$func_name($($arg,)*);
-            $invocation.return_value((result,));
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
-        }
+    ($class_name:ident, $interface_name:expr, @properties [$($names:ident : $types:tt),*] property $name:ident : $property_type:ty $(, $setter:ident)* ; $($rest:tt)*) => {
+        dbus_proxy_properties!($class_name, $interface_name, @properties [$($names : $types,)* $name : $property_type] $($rest)*);
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident () -> () $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            DBusObject::$func_name();
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
-        }
+    ($class_name:ident, $interface_name:expr, @properties [$($names:ident : $types:tt),*] signal $name:ident $args:tt, $connect:ident ; $($rest:tt)*) => {
+        dbus_proxy_properties!($class_name, $interface_name, @properties [$($names : $types),*] $($rest)*);
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident () -> ($($return_type:ty),*) $block:block $($rest:tt)*) => {
-        if $method_name == stringify!($func_name) {
-            let result = DBusObject::$func_name();
-            $invocation.return_value(result);
-        }
-        else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
-        }
+    ($class_name:ident, $interface_name:expr, @properties [$($names:ident : $types:tt),*] fn $func_name:ident $args:tt -> $return_type:ty $(, $async_name:ident)* ; $($rest:tt)*) => {
+        dbus_proxy_properties!($class_name, $interface_name, @properties [$($names : $types),*] $($rest)*);
+    };
+    ($class_name:ident, $interface_name:expr, @properties [$($names:ident : $types:tt),*] fn $func_name:ident $args:tt $(, $async_name:ident)+ ; $($rest:tt)*) => {
+        dbus_proxy_properties!($class_name, $interface_name, @properties [$($names : $types),*] $($rest)*);
+    };
+    ($class_name:ident, $interface_name:expr, @properties [$($names:ident : $types:tt),*] fn $func_name:ident $args:tt ; $($rest:tt)*) => {
+        dbus_proxy_properties!($class_name, $interface_name, @properties [$($names : $types),*] $($rest)*);
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident () -> $return_type:ty $block:block $($rest:tt)*) => {
+    ($class_name:ident, $interface_name:expr, $($rest:tt)*) => {
+        dbus_proxy_properties!($class_name, $interface_name, @properties [] $($rest)*);
+    };
+}
+
+#[macro_export]
+macro_rules! dbus_functions {
+    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, $finished:expr,) => {
+        Some($finished)
+    };
+    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, $finished:expr, #[annotation($($annotation:tt)*)] $($rest:tt)*) => {
+        dbus_functions!($object, $method_name, $args, $invocation, $finished, $($rest)*)
+    };
+    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, $finished:expr, property $property_name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        dbus_functions!($object, $method_name, $args, $invocation, $finished, $($rest)*)
+    };
+    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, $finished:expr, fn $func_name:ident (& $(mut)* self) -> $return_type:ty $block:block $($rest:tt)*) => {
         if $method_name == stringify!($func_name) {
-            let result = DBusObject::$func_name();
-            $invocation.return_value((result,));
+            let this = &mut *$object.borrow_mut();
+            let result = this.$func_name();
+            ::gdbus::method_invocation::MethodResult::return_to_then(result, $invocation, $finished)
         }
         else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
+            dbus_functions!($object, $method_name, $args, $invocation, $finished, $($rest)*)
         }
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident ($($arg:ident : $arg_type:ty),*) -> () $block:block $($rest:tt)*) => {
+    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, $finished:expr, fn $func_name:ident (& $(mut)* self, $($arg:ident : $arg_type:ty),*) -> $return_type:ty $block:block $($rest:tt)*) => {
         if $method_name == stringify!($func_name) {
+            let this = &mut *$object.borrow_mut();
             let ($($arg,)*): ($($arg_type,)*) = ::gdbus::variant::FromVariant::from_variant(&$args);
-            DBusObject::$func_name($($arg,)*);
+            let result = this.$func_name($($arg,)*);
+            ::gdbus::method_invocation::MethodResult::return_to_then(result, $invocation, $finished)
         }
         else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
+            dbus_functions!($object, $method_name, $args, $invocation, $finished, $($rest)*)
         }
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident ($($arg:ident : $arg_type:ty),*) -> ($($return_type:ty),*) $block:block $($rest:tt)*) => {
+    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, $finished:expr, fn $func_name:ident () -> $return_type:ty $block:block $($rest:tt)*) => {
         if $method_name == stringify!($func_name) {
-            let ($($arg,)*): ($($arg_type,)*) = ::gdbus::variant::FromVariant::from_variant(&$args);
-            let result = DBusObject::$func_name($($arg,)*);
-            $invocation.return_value(result);
+            let result = DBusObject::$func_name();
+            ::gdbus::method_invocation::MethodResult::return_to_then(result, $invocation, $finished)
         }
         else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
+            dbus_functions!($object, $method_name, $args, $invocation, $finished, $($rest)*)
         }
     };
-    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, fn $func_name:ident ($($arg:ident : $arg_type:ty),*) -> $return_type:ty $block:block $($rest:tt)*) => {
+    ($object:expr, $method_name:expr, $args:expr, $invocation:expr, $finished:expr, fn $func_name:ident ($($arg:ident : $arg_type:ty),*) -> $return_type:ty $block:block $($rest:tt)*) => {
         if $method_name == stringify!($func_name) {
             let ($($arg,)*): ($($arg_type,)*) = ::gdbus::variant::FromVariant::from_variant(&$args);
             let result = DBusObject::$func_name($($arg,)*);
-            $invocation.return_value((result,));
+            ::gdbus::method_invocation::MethodResult::return_to_then(result, $invocation, $finished)
         }
         else {
-            dbus_functions!($object, $method_name, $args, $invocation, $($rest)*);
+            dbus_functions!($object, $method_name, $args, $invocation, $finished, $($rest)*)
         }
     };
 }
@@ -328,15 +393,51 @@ macro_rules! dbus_arg_signature {
     (u32) => { "u" };
     (i64) => { "x" };
     (u64) => { "t" };
+    (f64) => { "d" };
     (&str) => { "s" };
     (String) => { "s" };
 }
 
+#[macro_export]
+macro_rules! dbus_annotations {
+    () => {
+        0 as *mut _
+    };
+    ($($name:tt = $value:tt),+) => {
+        &[
+            $(
+            &::gio_sys::GDBusAnnotationInfo {
+                ref_count: ::glib_sys::Volatile(-1),
+                key: c_str!($name),
+                value: c_str!($value),
+                annotations: 0 as *mut _,
+            } as *const _ as *mut ::gio_sys::GDBusAnnotationInfo,
+            )+
+            0 as *mut ::gio_sys::GDBusAnnotationInfo
+        ] as *const _ as *mut _
+    };
+}
+
 #[macro_export]
 macro_rules! dbus_methods {
     () => {
     };
-    (fn $func_name:ident (& $(mut)* self $(,$arg:ident : $($arg_type:tt)*)*) -> ($($return_type:tt),*) $block:block $($rest:tt)*) => {
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* property $property_name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        dbus_methods!($($rest)*);
+    };
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident ($($args:tt)*) -> Async<Result<$return_type:tt, $error_type:ty>> $block:block $($rest:tt)*) => {
+        dbus_methods!($(#[annotation($annotation_name = $annotation_value)])* fn $func_name ($($args)*) -> $return_type $block $($rest)*);
+    };
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident ($($args:tt)*) -> Async<$return_type:tt> $block:block $($rest:tt)*) => {
+        dbus_methods!($(#[annotation($annotation_name = $annotation_value)])* fn $func_name ($($args)*) -> $return_type $block $($rest)*);
+    };
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident ($($args:tt)*) -> Reply<$return_type:tt> $block:block $($rest:tt)*) => {
+        dbus_methods!($(#[annotation($annotation_name = $annotation_value)])* fn $func_name ($($args)*) -> $return_type $block $($rest)*);
+    };
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident ($($args:tt)*) -> Result<$return_type:tt, $error_type:ty> $block:block $($rest:tt)*) => {
+        dbus_methods!($(#[annotation($annotation_name = $annotation_value)])* fn $func_name ($($args)*) -> $return_type $block $($rest)*);
+    };
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident (& $(mut)* self $(,$arg:ident : $($arg_type:tt)*)*) -> ($($return_type:tt),*) $block:block $($rest:tt)*) => {
         const $func_name: *mut ::gio_sys::GDBusMethodInfo = {
             $(
             const $arg: ::gio_sys::GDBusArgInfo = ::gio_sys::GDBusArgInfo {
@@ -365,13 +466,13 @@ macro_rules! dbus_methods {
                 name: c_stringify!($func_name),
                 in_args: &IN_ARGS as *const _ as *mut _,
                 out_args: &OUT_ARGS as *const _ as *mut _,
-                annotations: 0 as *mut _,
+                annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
             } as *const _ as *mut _
         };
 
         dbus_methods!($($rest)*);
     };
-    (fn $func_name:ident (& $(mut)* self $(,$arg:ident : $($arg_type:tt)*)*) -> $return_type:tt $block:block $($rest:tt)*) => {
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident (& $(mut)* self $(,$arg:ident : $($arg_type:tt)*)*) -> $return_type:tt $block:block $($rest:tt)*) => {
         const $func_name: *mut ::gio_sys::GDBusMethodInfo = {
             $(
             const $arg: ::gio_sys::GDBusArgInfo = ::gio_sys::GDBusArgInfo {
@@ -398,13 +499,13 @@ macro_rules! dbus_methods {
                 name: c_stringify!($func_name),
                 in_args: &IN_ARGS as *const _ as *mut _,
                 out_args: &OUT_ARGS as *const _ as *mut _,
-                annotations: 0 as *mut _,
+                annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
             } as *const _ as *mut _
         };
 
         dbus_methods!($($rest)*);
     };
-    (fn $func_name:ident (& $(mut)* self $(,$arg:ident : $($arg_type:tt)*)*) $block:block $($rest:tt)*) => {
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident (& $(mut)* self $(,$arg:ident : $($arg_type:tt)*)*) $block:block $($rest:tt)*) => {
         const $func_name: *mut ::gio_sys::GDBusMethodInfo = {
             $(
             const $arg: ::gio_sys::GDBusArgInfo = ::gio_sys::GDBusArgInfo {
@@ -424,13 +525,13 @@ macro_rules! dbus_methods {
                 name: c_stringify!($func_name),
                 in_args: &IN_ARGS as *const _ as *mut _,
                 out_args: &OUT_ARGS as *const _ as *mut _,
-                annotations: 0 as *mut _,
+                annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
             } as *const _ as *mut _
         };
 
         dbus_methods!($($rest)*);
     };
-    (fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) -> ($($return_type:tt),*) $block:block $($rest:tt)*) => {
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) -> ($($return_type:tt),*) $block:block $($rest:tt)*) => {
         const $func_name: *mut ::gio_sys::GDBusMethodInfo = {
             $(
             const $arg: ::gio_sys::GDBusArgInfo = ::gio_sys::GDBusArgInfo {
@@ -459,13 +560,13 @@ macro_rules! dbus_methods {
                 name: c_stringify!($func_name),
                 in_args: &IN_ARGS as *const _ as *mut _,
                 out_args: &OUT_ARGS as *const _ as *mut _,
-                annotations: 0 as *mut _,
+                annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
             } as *const _ as *mut _
         };
 
         dbus_methods!($($rest)*);
     };
-    (fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) -> $return_type:tt $block:block $($rest:tt)*) => {
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) -> $return_type:tt $block:block $($rest:tt)*) => {
         const $func_name: *mut ::gio_sys::GDBusMethodInfo = {
             $(
             const $arg: ::gio_sys::GDBusArgInfo = ::gio_sys::GDBusArgInfo {
@@ -492,13 +593,13 @@ macro_rules! dbus_methods {
                 name: c_stringify!($func_name),
                 in_args: &IN_ARGS as *const _ as *mut _,
                 out_args: &OUT_ARGS as *const _ as *mut _,
-                annotations: 0 as *mut _,
+                annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
             } as *const _ as *mut _
         };
 
         dbus_methods!($($rest)*);
     };
-    (fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) $block:block $($rest:tt)*) => {
+    ($(#[annotation($annotation_name:tt = $annotation_value:tt)])* fn $func_name:ident ($($arg:ident : $($arg_type:tt)*),*) $block:block $($rest:tt)*) => {
         const $func_name: *mut ::gio_sys::GDBusMethodInfo = {
             $(
             const $arg: ::gio_sys::GDBusArgInfo = ::gio_sys::GDBusArgInfo {
@@ -518,7 +619,7 @@ macro_rules! dbus_methods {
                 name: c_stringify!($func_name),
                 in_args: &IN_ARGS as *const _ as *mut _,
                 out_args: &OUT_ARGS as *const _ as *mut _,
-                annotations: 0 as *mut _,
+                annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
             } as *const _ as *mut _
         };
 
@@ -538,14 +639,35 @@ macro_rules! dbus_count_idents {
 
 #[macro_export]
 macro_rules! dbus_function_names {
-    ($(fn $func_name:ident ( $($tt:tt)* ) -> $return_type:ty $block:block)*) => {
-        [$($func_name),*, 0 as *mut _]
+    (@names [$($names:ident)*]) => {
+        [$($names,)* 0 as *mut _]
+    };
+    (@names [$($names:ident)*] #[annotation($($annotation:tt)*)] $($rest:tt)*) => {
+        dbus_function_names!(@names [$($names)*] $($rest)*)
+    };
+    (@names [$($names:ident)*] property $property_name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        dbus_function_names!(@names [$($names)*] $($rest)*)
+    };
+    (@names [$($names:ident)*] fn $func_name:ident ( $($tt:tt)* ) -> $return_type:ty $block:block $($rest:tt)*) => {
+        dbus_function_names!(@names [$($names)* $func_name] $($rest)*)
+    };
+    (@names [$($names:ident)*] fn $func_name:ident ( $($tt:tt)* ) $block:block $($rest:tt)*) => {
+        dbus_function_names!(@names [$($names)* $func_name] $($rest)*)
+    };
+    ($($rest:tt)*) => {
+        dbus_function_names!(@names [] $($rest)*)
     };
 }
 
 #[macro_export]
 macro_rules! dbus_count_methods {
     () => { 0usize };
+    (#[annotation($($annotation:tt)*)] $($rest:tt)*) => {
+        dbus_count_methods!($($rest)*)
+    };
+    (property $property_name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        dbus_count_methods!($($rest)*)
+    };
     (fn $func_name:ident ( $($tt:tt)* ) -> $return_type:ty $block:block $($rest:tt)*) => {
         replace_expr!($func_name 1usize)
             + dbus_count_methods!($($rest)*)
@@ -556,73 +678,476 @@ macro_rules! dbus_count_methods {
     };
 }
 
+#[macro_export]
+macro_rules! dbus_object_functions {
+    () => {
+    };
+    (#[annotation($($annotation:tt)*)] $($rest:tt)*) => {
+        dbus_object_functions!($($rest)*);
+    };
+    (property $property_name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        dbus_object_functions!($($rest)*);
+    };
+    (fn $func_name:ident $args:tt -> $return_type:ty $block:block $($rest:tt)*) => {
+        fn $func_name $args -> $return_type $block
+
+        dbus_object_functions!($($rest)*);
+    };
+}
+
+#[macro_export]
+macro_rules! dbus_property_flags {
+    (read) => { 1 };
+    (write) => { 2 };
+    (readwrite) => { 3 };
+}
+
+#[macro_export]
+macro_rules! dbus_properties {
+    (@infos [$($infos:expr),*]) => {
+        &[$($infos,)* 0 as *mut ::gio_sys::GDBusPropertyInfo] as *const _ as *mut _
+    };
+    (@infos [$($infos:expr),*] $(#[annotation($annotation_name:tt = $annotation_value:tt)])* property $property_name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        dbus_properties!(@infos [$($infos,)* &::gdbus::node_info::StaticPropertyInfo {
+            ref_count: ::glib_sys::Volatile(-1),
+            name: c_stringify!($property_name),
+            signature: c_str!(dbus_arg_signature!($property_type)),
+            flags: dbus_property_flags!($access),
+            annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
+        } as *const _ as *mut ::gio_sys::GDBusPropertyInfo] $($rest)*)
+    };
+    (@infos [$($infos:expr),*] $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt -> $return_type:ty $block:block $($rest:tt)*) => {
+        dbus_properties!(@infos [$($infos),*] $($rest)*)
+    };
+    (@infos [$($infos:expr),*] $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt $block:block $($rest:tt)*) => {
+        dbus_properties!(@infos [$($infos),*] $($rest)*)
+    };
+    ($($rest:tt)*) => {
+        dbus_properties!(@infos [] $($rest)*)
+    };
+}
+
+#[macro_export]
+macro_rules! dbus_property_values {
+    (@readable [$($interface_annotations:tt),*] [$($names:ident : $types:tt [$($annotations:tt),*]),*]) => {
+        #[derive(Clone, PartialEq)]
+        struct DBusProperties {
+            $($names: $types,)*
+        }
+
+        impl DBusProperties {
+            #[allow(unused_variables)]
+            fn new(object: &DBusObject) -> Self {
+                DBusProperties {
+                    $($names: object.$names.clone(),)*
+                }
+            }
+
+            /// Get the changed values and the names of the invalidated properties, according to their `EmitsChangedSignal` annotation.
+            #[allow(unused_mut, unused_variables)]
+            fn changed(&self, other: &Self) -> (Vec<(String, ::gdbus::variant::Variant)>, Vec<&'static str>) {
+                let interface_annotations: &[(&str, &str)] = &[$($interface_annotations),*];
+                let mut changed = vec![];
+                let mut invalidated = vec![];
+                $(
+                if self.$names != other.$names {
+                    match ::gdbus::interface::emits_changed_signal(&[$($annotations),*], interface_annotations) {
+                        ::gdbus::interface::EmitsChangedSignal::True => changed.push((stringify!($names).to_string(), ::gdbus::variant::Variant::from_value(&other.$names))),
+                        ::gdbus::interface::EmitsChangedSignal::Invalidates => invalidated.push(stringify!($names)),
+                        ::gdbus::interface::EmitsChangedSignal::Const | ::gdbus::interface::EmitsChangedSignal::False => (),
+                    }
+                }
+                )*
+                (changed, invalidated)
+            }
+
+            #[allow(dead_code)]
+            fn values(&self) -> Vec<(String, ::gdbus::variant::Variant)> {
+                vec![$((stringify!($names).to_string(), ::gdbus::variant::Variant::from_value(&self.$names)),)*]
+            }
+        }
+    };
+    (@readable [$($interface_annotations:tt),*] [$($names:ident : $types:tt [$($annotations:tt),*]),*] $(#[annotation($($annotation:tt)*)])* property $property_name:ident : $property_type:tt (write) $($rest:tt)*) => {
+        dbus_property_values!(@readable [$($interface_annotations),*] [$($names : $types [$($annotations),*]),*] $($rest)*);
+    };
+    (@readable [$($interface_annotations:tt),*] [$($names:ident : $types:tt [$($annotations:tt),*]),*] $(#[annotation($annotation_name:tt = $annotation_value:tt)])* property $property_name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        dbus_property_values!(@readable [$($interface_annotations),*] [$($names : $types [$($annotations),*],)* $property_name : $property_type [$(($annotation_name, $annotation_value)),*]] $($rest)*);
+    };
+    (@readable [$($interface_annotations:tt),*] [$($names:ident : $types:tt [$($annotations:tt),*]),*] $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt -> $return_type:ty $block:block $($rest:tt)*) => {
+        dbus_property_values!(@readable [$($interface_annotations),*] [$($names : $types [$($annotations),*]),*] $($rest)*);
+    };
+    (@readable [$($interface_annotations:tt),*] [$($names:ident : $types:tt [$($annotations:tt),*]),*] $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt $block:block $($rest:tt)*) => {
+        dbus_property_values!(@readable [$($interface_annotations),*] [$($names : $types [$($annotations),*]),*] $($rest)*);
+    };
+    (@interface [$($annotation_name:tt = $annotation_value:tt),*] $($rest:tt)*) => {
+        dbus_property_values!(@readable [$(($annotation_name, $annotation_value)),*] [] $($rest)*);
+    };
+    ($($rest:tt)*) => {
+        dbus_property_values!(@readable [] [] $($rest)*);
+    };
+}
+
+#[macro_export]
+macro_rules! dbus_get_property {
+    ($object:expr, $property_name:expr,) => {
+        None
+    };
+    ($object:expr, $property_name:expr, $(#[annotation($($annotation:tt)*)])* property $name:ident : $property_type:tt (write) $($rest:tt)*) => {
+        dbus_get_property!($object, $property_name, $($rest)*)
+    };
+    ($object:expr, $property_name:expr, $(#[annotation($($annotation:tt)*)])* property $name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        if $property_name == stringify!($name) {
+            Some(::gdbus::variant::Variant::from_value(&$object.borrow().$name))
+        }
+        else {
+            dbus_get_property!($object, $property_name, $($rest)*)
+        }
+    };
+    ($object:expr, $property_name:expr, $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt -> $return_type:ty $block:block $($rest:tt)*) => {
+        dbus_get_property!($object, $property_name, $($rest)*)
+    };
+    ($object:expr, $property_name:expr, $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt $block:block $($rest:tt)*) => {
+        dbus_get_property!($object, $property_name, $($rest)*)
+    };
+}
+
+#[macro_export]
+macro_rules! dbus_set_property {
+    ($object:expr, $property_name:expr, $value:expr,) => {
+        false
+    };
+    ($object:expr, $property_name:expr, $value:expr, $(#[annotation($($annotation:tt)*)])* property $name:ident : $property_type:tt (read) $($rest:tt)*) => {
+        dbus_set_property!($object, $property_name, $value, $($rest)*)
+    };
+    ($object:expr, $property_name:expr, $value:expr, $(#[annotation($($annotation:tt)*)])* property $name:ident : $property_type:tt ($access:ident) $($rest:tt)*) => {
+        if $property_name == stringify!($name) {
+            $object.borrow_mut().$name = $value.get_value();
+            true
+        }
+        else {
+            dbus_set_property!($object, $property_name, $value, $($rest)*)
+        }
+    };
+    ($object:expr, $property_name:expr, $value:expr, $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt -> $return_type:ty $block:block $($rest:tt)*) => {
+        dbus_set_property!($object, $property_name, $value, $($rest)*)
+    };
+    ($object:expr, $property_name:expr, $value:expr, $(#[annotation($($annotation:tt)*)])* fn $func_name:ident $args:tt $block:block $($rest:tt)*) => {
+        dbus_set_property!($object, $property_name, $value, $($rest)*)
+    };
+}
+
 #[macro_export]
 macro_rules! dbus_class {
-    ($interface_name:expr, class $class_name:ident $(($($variables:ident : $variable_types:ty),+))* { $($functions:tt)+ }) => {
+    (class $class_name:ident $(($($variables:ident : $variable_types:ty),+))* { $(#[dbus($interface_name:expr)] $(#[annotation($annotation_name:tt = $annotation_value:tt)])* interface { $($functions:tt)* })+ }) => {
         struct DBusObject {
             $($($variables : $variable_types,)*)*
         }
 
         impl DBusObject {
-            $($functions)+
+            $(dbus_object_functions!($($functions)*);)+
         }
 
-        #[derive(Clone)]
         pub struct $class_name {
+            __inner_gdbus_bus_type: ::gdbus::own_name::Type,
+            __inner_gdbus_connection: Option<::gdbus::connection::Connection>,
             __inner_gdbus_dbus_name: String,
-            __inner_gdbus_own_name: ::gdbus::own_name::OwnName,
-            object: ::std::rc::Rc<::std::cell::RefCell<DBusObject>>,
+            __inner_gdbus_objects: Vec<(String, ::std::rc::Rc<::std::cell::RefCell<DBusObject>>)>,
+            __inner_gdbus_own_name: Option<::gdbus::own_name::OwnName>,
+            __inner_gdbus_owner_flags: ::gdbus::own_name::NameOwnerFlags,
+            __inner_gdbus_registration: Option<(::gdbus::connection::Connection, Vec<(String, Vec<u32>)>)>,
         }
 
         impl $class_name {
             pub fn new(dbus_name: &str $(, $($variables: $variable_types),*)*) -> Self {
+                let object = DBusObject {
+                    $($($variables : $variables,)*)*
+                };
                 $class_name {
+                    __inner_gdbus_bus_type: ::gdbus::own_name::Type::Session,
+                    __inner_gdbus_connection: None,
                     __inner_gdbus_dbus_name: dbus_name.to_string(),
-                    __inner_gdbus_own_name: ::gdbus::own_name::OwnName::from_id(0),
-                    object: ::std::rc::Rc::new(::std::cell::RefCell::new(DBusObject {
-                        $($($variables : $variables,)*)*
-                    })),
+                    __inner_gdbus_objects: vec![(format!("/{}", dbus_name.replace(".", "/")), ::std::rc::Rc::new(::std::cell::RefCell::new(object)))],
+                    __inner_gdbus_own_name: None,
+                    __inner_gdbus_owner_flags: ::gdbus::own_name::NAME_OWNER_FLAGS_NONE,
+                    __inner_gdbus_registration: None,
                 }
             }
 
-            fn handle_method_call(&self, method_name: &str, _args: ::gdbus::variant::Variant, invocation: &::gdbus::method_invocation::MethodInvocation) {
-                dbus_functions!(self.object, method_name, _args, invocation, $($functions)+);
+            pub fn bus_type(mut self, bus_type: ::gdbus::own_name::Type) -> Self {
+                self.__inner_gdbus_bus_type = bus_type;
+                self
             }
 
-            pub fn run(&mut self, bus_name: &str) {
-                dbus_methods!($($functions)+);
-                const METHODS: [*mut ::gio_sys::GDBusMethodInfo; dbus_count_methods!($($functions)+) + 1usize] = dbus_function_names!($($functions)+);
+            pub fn connection(mut self, connection: &::gdbus::connection::Connection) -> Self {
+                self.__inner_gdbus_connection = Some(connection.clone());
+                self
+            }
 
-                const INTERFACE: ::gio_sys::GDBusInterfaceInfo = ::gio_sys::GDBusInterfaceInfo {
-                    ref_count: ::glib_sys::Volatile(-1),
-                    name: c_str!($interface_name),
-                    methods: &METHODS as *const _ as *mut _,
-                    signals: 0 as *mut _,
-                    properties: 0 as *mut _,
-                    annotations: 0 as *mut _,
-                };
+            pub fn object_path(mut self, object_path: &str) -> Self {
+                self.__inner_gdbus_objects[0].0 = object_path.to_string();
+                self
+            }
 
-                const INTERFACES: [*mut ::gio_sys::GDBusInterfaceInfo; 2] = [&INTERFACE as *const _ as *mut _, 0 as *mut _];
+            pub fn owner_flags(mut self, flags: ::gdbus::own_name::NameOwnerFlags) -> Self {
+                self.__inner_gdbus_owner_flags = flags;
+                self
+            }
 
-                const NODE: ::gio_sys::GDBusNodeInfo = ::gio_sys::GDBusNodeInfo {
+            pub fn is_owner(&self) -> bool {
+                self.__inner_gdbus_own_name.as_ref().map(|own_name| own_name.is_owner()).unwrap_or(false)
+            }
+
+            /// Export another object, with its own state, at `object_path`.
+            /// The object is registered immediately if the class is running.
+            pub fn add_object(&mut self, object_path: &str $(, $($variables: $variable_types),*)*) -> Result<(), ::gdbus::Error> {
+                let object = ::std::rc::Rc::new(::std::cell::RefCell::new(DBusObject {
+                    $($($variables : $variables,)*)*
+                }));
+                if let Some((ref connection, ref mut registrations)) = self.__inner_gdbus_registration {
+                    let ids = try!($class_name::register_object(connection, object_path, &object));
+                    registrations.push((object_path.to_string(), ids));
+                }
+                self.__inner_gdbus_objects.push((object_path.to_string(), object));
+                Ok(())
+            }
+
+            /// Stop exporting the object at `object_path`.
+            /// Returns `false` if there is no object at this path.
+            pub fn remove_object(&mut self, object_path: &str) -> bool {
+                if let Some((ref connection, ref mut registrations)) = self.__inner_gdbus_registration {
+                    for &(_, ref ids) in registrations.iter().filter(|&&(ref path, _)| path == object_path) {
+                        for &id in ids {
+                            connection.unregister_object(id);
+                        }
+                    }
+                    registrations.retain(|&(ref path, _)| path != object_path);
+                }
+                let count = self.__inner_gdbus_objects.len();
+                self.__inner_gdbus_objects.retain(|&(ref path, _)| path != object_path);
+                self.__inner_gdbus_objects.len() != count
+            }
+
+            fn register_object(connection: &::gdbus::connection::Connection, object_path: &str, object: &::std::rc::Rc<::std::cell::RefCell<DBusObject>>) -> Result<Vec<u32>, ::gdbus::Error> {
+                let node_info = $class_name::node_info();
+                let mut ids = vec![];
+                $(
+                {
+                    dbus_property_values!(@interface [$($annotation_name = $annotation_value),*] $($functions)*);
+
+                    fn handle_method_call(object: &::std::rc::Rc<::std::cell::RefCell<DBusObject>>, method_name: &str, _args: ::gdbus::variant::Variant, invocation: ::gdbus::method_invocation::MethodInvocation) {
+                        let connection = invocation.connection();
+                        let object_path = invocation.object_path();
+                        let properties = DBusProperties::new(&object.borrow());
+                        let changed_object = object.clone();
+                        // Asynchronous methods can change the properties until they reply.
+                        let finished = move || emit_properties_changed(&connection, &object_path, &properties, &changed_object);
+                        if let Some(finished) = dbus_functions!(object, method_name, _args, invocation, finished, $($functions)*) {
+                            finished();
+                        }
+                    }
+
+                    fn get_property(_object: &::std::rc::Rc<::std::cell::RefCell<DBusObject>>, _property_name: &str) -> Option<::gdbus::variant::Variant> {
+                        dbus_get_property!(_object, _property_name, $($functions)*)
+                    }
+
+                    fn set_property(_object: &::std::rc::Rc<::std::cell::RefCell<DBusObject>>, _property_name: &str, _value: ::gdbus::variant::Variant) -> bool {
+                        dbus_set_property!(_object, _property_name, _value, $($functions)*)
+                    }
+
+                    fn emit_properties_changed(connection: &::gdbus::connection::Connection, object_path: &str, old_properties: &DBusProperties, object: &::std::rc::Rc<::std::cell::RefCell<DBusObject>>) {
+                        let (changed, invalidated) = old_properties.changed(&DBusProperties::new(&object.borrow()));
+                        if !changed.is_empty() || !invalidated.is_empty() {
+                            connection.emit_properties_changed(object_path, $interface_name, changed, &invalidated).ok();
+                        }
+                    }
+
+                    let method_object = object.clone();
+                    let get_object = object.clone();
+                    let set_object = object.clone();
+                    let set_connection = connection.clone();
+                    let set_object_path = object_path.to_string();
+                    let interface = node_info.lookup_interface($interface_name).expect("interface in node info");
+                    let result = connection.register_object_with_properties(object_path, interface,
+                        move |method_name, args, invocation| handle_method_call(&method_object, method_name, args, invocation),
+                        move |property_name| get_property(&get_object, property_name),
+                        move |property_name, value| {
+                            let properties = DBusProperties::new(&set_object.borrow());
+                            let result = set_property(&set_object, property_name, value);
+                            emit_properties_changed(&set_connection, &set_object_path, &properties, &set_object);
+                            result
+                        });
+                    match result {
+                        Ok(id) => ids.push(id),
+                        Err(error) => {
+                            for id in ids {
+                                connection.unregister_object(id);
+                            }
+                            return Err(error);
+                        },
+                    }
+                }
+                )+
+                Ok(ids)
+            }
+
+            fn object_interfaces(object: &::std::rc::Rc<::std::cell::RefCell<DBusObject>>) -> ::gdbus::object_manager::InterfacesAndProperties {
+                vec![$({
+                    dbus_property_values!(@interface [$($annotation_name = $annotation_value),*] $($functions)*);
+                    ($interface_name.to_string(), DBusProperties::new(&object.borrow()).values())
+                },)+]
+            }
+
+            pub fn introspection_xml() -> String {
+                $class_name::node_info().to_xml()
+            }
+
+            fn node_info() -> ::gdbus::node_info::NodeInfo {
+                const NODE: *mut ::gio_sys::GDBusNodeInfo = &::gio_sys::GDBusNodeInfo {
                     ref_count: ::glib_sys::Volatile(-1),
                     path: 0 as *mut _,
-                    interfaces: &INTERFACES as *const _ as *mut _,
+                    interfaces: &[
+                        $({
+                            dbus_methods!($($functions)*);
+                            const METHODS: [*mut ::gio_sys::GDBusMethodInfo; dbus_count_methods!($($functions)*) + 1usize] = dbus_function_names!($($functions)*);
+
+                            const INTERFACE: ::gio_sys::GDBusInterfaceInfo = ::gio_sys::GDBusInterfaceInfo {
+                                ref_count: ::glib_sys::Volatile(-1),
+                                name: c_str!($interface_name),
+                                methods: &METHODS as *const _ as *mut _,
+                                signals: 0 as *mut _,
+                                properties: dbus_properties!($($functions)*),
+                                annotations: dbus_annotations!($($annotation_name = $annotation_value),*),
+                            };
+
+                            &INTERFACE as *const _ as *mut ::gio_sys::GDBusInterfaceInfo
+                        },)+
+                        0 as *mut ::gio_sys::GDBusInterfaceInfo
+                    ] as *const _ as *mut _,
                     nodes: 0 as *mut _,
                     annotations: 0 as *mut _,
-                };
+                } as *const _ as *mut _;
+
+                ::gdbus::node_info::NodeInfo::new(NODE)
+            }
+
+            pub fn run(&mut self) -> Result<(), ::gdbus::Error> {
+                self.stop();
+                let connection =
+                    match self.__inner_gdbus_connection {
+                        Some(ref connection) => connection.clone(),
+                        None => try!(::gdbus::connection::Connection::get_sync(self.__inner_gdbus_bus_type)),
+                    };
+                let mut registrations = vec![];
+                for &(ref object_path, ref object) in &self.__inner_gdbus_objects {
+                    match $class_name::register_object(&connection, object_path, object) {
+                        Ok(ids) => registrations.push((object_path.clone(), ids)),
+                        Err(error) => {
+                            for (_, ids) in registrations {
+                                for id in ids {
+                                    connection.unregister_object(id);
+                                }
+                            }
+                            return Err(error);
+                        },
+                    }
+                }
+                self.__inner_gdbus_registration = Some((connection.clone(), registrations));
+                self.__inner_gdbus_own_name = Some(::gdbus::own_name::OwnName::new_on_connection(&connection, &self.__inner_gdbus_dbus_name, self.__inner_gdbus_owner_flags).build());
+                Ok(())
+            }
+
+            pub fn stop(&mut self) {
+                self.__inner_gdbus_own_name.take();
+                if let Some((connection, registrations)) = self.__inner_gdbus_registration.take() {
+                    for (_, ids) in registrations {
+                        for id in ids {
+                            connection.unregister_object(id);
+                        }
+                    }
+                }
+            }
+        }
+
+        impl ::gdbus::object_manager::ManagedObject for $class_name {
+            fn register(&self, connection: &::gdbus::connection::Connection, object_path: &str) -> Result<Vec<u32>, ::gdbus::Error> {
+                if self.__inner_gdbus_registration.is_some() {
+                    return Err(::gdbus::object_manager::invalid_args_error(concat!("A running ", stringify!($class_name), " cannot be exported by an object manager")));
+                }
+                match *self.__inner_gdbus_objects.as_slice() {
+                    [(_, ref object)] => $class_name::register_object(connection, object_path, object),
+                    _ => Err(::gdbus::object_manager::invalid_args_error(concat!("Only a ", stringify!($class_name), " with a single object can be exported by an object manager"))),
+                }
+            }
+
+            fn interfaces(&self) -> ::gdbus::object_manager::InterfacesAndProperties {
+                match *self.__inner_gdbus_objects.as_slice() {
+                    [(_, ref object)] => $class_name::object_interfaces(object),
+                    _ => vec![],
+                }
+            }
+        }
 
-                let bus_name = bus_name.to_string();
-                let this = self.clone();
-                let old = ::std::mem::replace(&mut self.__inner_gdbus_own_name, ::gdbus::own_name::OwnName::new(::gdbus::own_name::Type::Session, &self.__inner_gdbus_dbus_name, ::gdbus::own_name::NAME_OWNER_FLAGS_NONE)
-                    .connect_bus_acquired(move |connection| {
-                        let introspection_data = ::gdbus::node_info::NodeInfo::new(&mut NODE);
-                        let this = this.clone();
-                        connection.register_object(&bus_name, introspection_data.interface(0), move |method_name, args, invocation| this.handle_method_call(method_name, args, invocation))
-                    })
-                    .build());
-                ::std::mem::forget(old);
+        impl Drop for $class_name {
+            fn drop(&mut self) {
+                self.stop();
+            }
+        }
+    };
+    ($interface_name:expr, $(#[annotation($annotation_name:tt = $annotation_value:tt)])* class $class_name:ident $(($($variables:ident : $variable_types:ty),+))* { $($functions:tt)+ }) => {
+        dbus_class!(class $class_name $(($($variables : $variable_types),+))* {
+            #[dbus($interface_name)]
+            $(#[annotation($annotation_name = $annotation_value)])*
+            interface {
+                $($functions)+
+            }
+        });
+    };
+}
+
+/// Define a type wrapping a `Variant` of the D-Bus type `$signature`, for the values without a Rust equivalent, like dictionaries and structures.
+/// Unlike a `Variant`, which is sent with the type `v`, the value is sent and advertised with its own type.
+#[macro_export]
+macro_rules! dbus_variant_type {
+    ($(#[$attr:meta])* pub struct $name:ident($signature:tt);) => {
+        $(#[$attr])*
+        #[derive(Clone, PartialEq)]
+        pub struct $name(pub ::gdbus::variant::Variant);
+
+        impl ::gdbus::variant::FromFFI for $name {
+            type Input = <::gdbus::variant::Variant as ::gdbus::variant::FromFFI>::Input;
+
+            unsafe fn from_ffi(input: Self::Input) -> Self {
+                $name(::gdbus::variant::FromFFI::from_ffi(input))
+            }
+        }
+
+        impl ::gdbus::variant::FromFormat for $name {
+            fn from_format() -> &'static str {
+                concat!("@", $signature)
+            }
+        }
+
+        impl ::gdbus::variant::ToFFI for $name {
+            type Output = <::gdbus::variant::Variant as ::gdbus::variant::ToFFI>::Output;
+
+            fn to_ffi(&self) -> Self::Output {
+                ::gdbus::variant::ToFFI::to_ffi(&self.0)
+            }
+        }
+
+        impl ::gdbus::variant::ToFormat for $name {
+            fn to_format() -> &'static str {
+                concat!("@", $signature)
+            }
+        }
+
+        impl ::gdbus::method_invocation::MethodResult for $name {
+            fn return_to(self, invocation: ::gdbus::method_invocation::MethodInvocation) {
+                invocation.return_value((self,));
+            }
 
+            fn out_signatures() -> Vec<&'static str> {
+                vec![concat!("@", $signature)]
             }
         }
     };
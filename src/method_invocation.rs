@@ -21,24 +21,350 @@
 
 //! `GDBusMethodInvocation` — Object for handling remote calls.
 
-use gio_sys::{GDBusMethodInvocation, g_dbus_method_invocation_return_value};
+use std::ffi::CString;
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
 
-use variant::ToVariant;
+use gio_sys::{GDBusMethodInvocation, g_dbus_error_encode_gerror, g_dbus_method_invocation_get_connection, g_dbus_method_invocation_get_interface_name, g_dbus_method_invocation_get_message, g_dbus_method_invocation_get_method_info, g_dbus_method_invocation_get_method_name, g_dbus_method_invocation_get_object_path, g_dbus_method_invocation_get_sender, g_dbus_method_invocation_return_dbus_error, g_dbus_method_invocation_return_error_literal, g_dbus_method_invocation_return_gerror, g_dbus_method_invocation_return_value};
+use glib::error::Error;
+use glib::translate::{from_glib_full, from_glib_none, ToGlibPtr};
+use glib_sys::g_quark_from_string;
+
+use connection::Connection;
+use future::{self, Async, Reply};
+use message::Message;
+use node_info::MethodInfo;
+use variant::{ObjectPath, Signature, ToFFI, ToFormat, ToVariant, Variant};
 
 /// Object for handling remote calls.
+/// The handler owns the invocation and can keep it, or send it to another thread, to reply later.
+/// Replying consumes the invocation, so a call is answered exactly once.
+/// If the invocation is dropped without a reply, the caller receives an `org.freedesktop.DBus.Error.Failed` error.
 pub struct MethodInvocation(*mut GDBusMethodInvocation);
 
+unsafe impl Send for MethodInvocation {}
+
 impl MethodInvocation {
     /// Create a new method invocation from a pointer.
     pub fn new(invocation: *mut GDBusMethodInvocation) -> Self {
         MethodInvocation(invocation)
     }
 
+    /// Gets the `GDBusConnection` the method was invoked on.
+    pub fn connection(&self) -> Connection {
+        Connection::new(unsafe { g_dbus_method_invocation_get_connection(self.0) })
+    }
+
+    /// Gets the name of the D-Bus interface the method was invoked on.
+    pub fn interface_name(&self) -> String {
+        unsafe { from_glib_none(g_dbus_method_invocation_get_interface_name(self.0)) }
+    }
+
+    /// Gets the `GDBusMessage` for the method invocation. This is useful if you need to use low-level protocol features, such as UNIX file descriptor passing, that cannot be properly expressed in the `GVariant` API.
+    pub fn message(&self) -> Message {
+        Message::new(unsafe { g_dbus_method_invocation_get_message(self.0) })
+    }
+
+    /// Gets information about the method call, if any.
+    pub fn method_info(&self) -> Option<MethodInfo> {
+        let method_info = unsafe { g_dbus_method_invocation_get_method_info(self.0) };
+        if method_info.is_null() {
+            None
+        }
+        else {
+            Some(MethodInfo::new(method_info as *mut _))
+        }
+    }
+
+    /// Gets the name of the method that was invoked.
+    pub fn method_name(&self) -> String {
+        unsafe { from_glib_none(g_dbus_method_invocation_get_method_name(self.0)) }
+    }
+
+    /// Gets the object path the method was invoked on.
+    pub fn object_path(&self) -> String {
+        unsafe { from_glib_none(g_dbus_method_invocation_get_object_path(self.0)) }
+    }
+
+    /// Gets the bus name that invoked the method.
+    /// This is `None` when the connection is a peer-to-peer connection rather than a message bus connection.
+    pub fn sender(&self) -> Option<String> {
+        unsafe { from_glib_none(g_dbus_method_invocation_get_sender(self.0)) }
+    }
+
+    /// Finishes handling a D-Bus method call by returning an error.
+    /// `error_name` must be a valid D-Bus error name, e.g. `org.freedesktop.DBus.Error.InvalidArgs`.
+    pub fn return_dbus_error(self, error_name: &str, error_message: &str) {
+        let error_name = CString::new(error_name).unwrap();
+        let error_message = CString::new(error_message).unwrap();
+        unsafe { g_dbus_method_invocation_return_dbus_error(self.into_raw(), error_name.as_ptr(), error_message.as_ptr()) };
+    }
+
+    /// Finishes handling a D-Bus method call by returning an error built from `domain`, `code` and `message`.
+    /// `domain` is the string form of the error domain quark, e.g. `g-io-error-quark`.
+    /// If the domain is not registered with `g_dbus_error_register_error()`, the caller receives an error named `org.gtk.GDBus.UnmappedGError.Quark...`.
+    pub fn return_error(self, domain: &str, code: i32, message: &str) {
+        let domain = CString::new(domain).unwrap();
+        let message = CString::new(message).unwrap();
+        unsafe {
+            let domain = g_quark_from_string(domain.as_ptr());
+            g_dbus_method_invocation_return_error_literal(self.into_raw(), domain, code, message.as_ptr());
+        }
+    }
+
+    /// Like `return_error()` but `error` is a `GError`.
+    pub fn return_gerror(self, error: &Error) {
+        unsafe { g_dbus_method_invocation_return_gerror(self.into_raw(), error.to_glib_none().0) };
+    }
+
     /// Finishes handling a D-Bus method call by returning `parameters`. If the `parameters` GVariant is floating, it is consumed.
     /// It is an error if `parameters` is not of the right format.
-    /// This method will free `invocation`, you cannot use it afterwards.
     /// Since 2.48, if the method call requested for a reply not to be sent then this call will sink `parameters` and free `invocation`, but otherwise do nothing (as per the recommendations of the D-Bus specification).
-    pub fn return_value<T: ToVariant>(&self, value: T) {
-        unsafe { g_dbus_method_invocation_return_value(self.0, value.to_variant().to_glib()) };
+    pub fn return_value<T: ToVariant>(self, value: T) {
+        unsafe { g_dbus_method_invocation_return_value(self.into_raw(), value.to_variant().to_glib()) };
+    }
+
+    /// Finishes handling a D-Bus method call with the value returned by a method handler.
+    /// A `Result` replies with its `Ok` value or with its `Err` converted to a D-Bus error.
+    pub fn return_result<T: MethodResult>(self, result: T) {
+        result.return_to(self);
+    }
+
+    fn into_raw(self) -> *mut GDBusMethodInvocation {
+        let invocation = self.0;
+        mem::forget(self);
+        invocation
+    }
+}
+
+impl Drop for MethodInvocation {
+    fn drop(&mut self) {
+        let error_name = CString::new("org.freedesktop.DBus.Error.Failed").unwrap();
+        let error_message = CString::new("The method call was dropped without a reply").unwrap();
+        unsafe { g_dbus_method_invocation_return_dbus_error(self.0, error_name.as_ptr(), error_message.as_ptr()) };
+    }
+}
+
+/// Trait for errors that can be sent back to the caller of a D-Bus method.
+pub trait DBusError {
+    /// The D-Bus error name, e.g. `org.freedesktop.DBus.Error.InvalidArgs`.
+    fn name(&self) -> String;
+
+    /// The human-readable error message.
+    fn message(&self) -> String;
+}
+
+impl DBusError for Error {
+    fn name(&self) -> String {
+        unsafe { from_glib_full(g_dbus_error_encode_gerror(self.to_glib_none().0)) }
+    }
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Trait for the values a method handler can reply with.
+pub trait MethodResult {
+    /// Finish `invocation` with this value.
+    fn return_to(self, invocation: MethodInvocation);
+
+    /// Finish `invocation` with this value and call `finished` once the reply is sent.
+    /// When the reply is sent right away, `finished` is returned instead, to be called once the caller released its borrows.
+    fn return_to_then<F: FnOnce() + 'static>(self, invocation: MethodInvocation, finished: F) -> Option<F> where Self: Sized {
+        self.return_to(invocation);
+        Some(finished)
+    }
+
+    /// The signatures of the values sent back to the caller, used for the introspection data.
+    fn out_signatures() -> Vec<&'static str> where Self: Sized {
+        vec![]
+    }
+}
+
+macro_rules! single_method_result {
+    ($($rust_type:ty),*) => {
+        $(
+        impl MethodResult for $rust_type {
+            fn return_to(self, invocation: MethodInvocation) {
+                invocation.return_value((self,));
+            }
+
+            fn out_signatures() -> Vec<&'static str> {
+                vec![<$rust_type as ToFormat>::to_format()]
+            }
+        }
+        )*
+    };
+}
+
+single_method_result!(bool, char, u8, i16, u16, i32, u32, i64, u64, f64, String, ObjectPath, Signature, Variant);
+single_method_result!(Vec<bool>, Vec<u8>, Vec<i16>, Vec<u16>, Vec<i32>, Vec<u32>, Vec<i64>, Vec<u64>, Vec<f64>, Vec<String>, Vec<ObjectPath>, Vec<Signature>, Vec<Variant>);
+
+impl<'a> MethodResult for &'a str {
+    fn return_to(self, invocation: MethodInvocation) {
+        invocation.return_value((self,));
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        vec![<&str as ToFormat>::to_format()]
+    }
+}
+
+impl MethodResult for () {
+    fn return_to(self, invocation: MethodInvocation) {
+        invocation.return_value(());
+    }
+}
+
+impl<P: ToFFI + ToFormat> MethodResult for (P,) {
+    fn return_to(self, invocation: MethodInvocation) {
+        invocation.return_value(self);
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        vec![P::to_format()]
+    }
+}
+
+impl<P: ToFFI + ToFormat, Q: ToFFI + ToFormat> MethodResult for (P, Q) {
+    fn return_to(self, invocation: MethodInvocation) {
+        invocation.return_value(self);
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        vec![P::to_format(), Q::to_format()]
+    }
+}
+
+impl<P: ToFFI + ToFormat, Q: ToFFI + ToFormat, R: ToFFI + ToFormat> MethodResult for (P, Q, R) {
+    fn return_to(self, invocation: MethodInvocation) {
+        invocation.return_value(self);
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        vec![P::to_format(), Q::to_format(), R::to_format()]
+    }
+}
+
+impl<P: ToFFI + ToFormat, Q: ToFFI + ToFormat, R: ToFFI + ToFormat, S: ToFFI + ToFormat> MethodResult for (P, Q, R, S) {
+    fn return_to(self, invocation: MethodInvocation) {
+        invocation.return_value(self);
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        vec![P::to_format(), Q::to_format(), R::to_format(), S::to_format()]
+    }
+}
+
+impl<P: ToFFI + ToFormat, Q: ToFFI + ToFormat, R: ToFFI + ToFormat, S: ToFFI + ToFormat, T: ToFFI + ToFormat> MethodResult for (P, Q, R, S, T) {
+    fn return_to(self, invocation: MethodInvocation) {
+        invocation.return_value(self);
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        vec![P::to_format(), Q::to_format(), R::to_format(), S::to_format(), T::to_format()]
+    }
+}
+
+impl<T: MethodResult, E: DBusError> MethodResult for Result<T, E> {
+    fn return_to(self, invocation: MethodInvocation) {
+        match self {
+            Ok(value) => value.return_to(invocation),
+            Err(error) => invocation.return_dbus_error(&error.name(), &error.message()),
+        }
+    }
+
+    fn return_to_then<F: FnOnce() + 'static>(self, invocation: MethodInvocation, finished: F) -> Option<F> {
+        match self {
+            Ok(value) => value.return_to_then(invocation, finished),
+            Err(error) => {
+                invocation.return_dbus_error(&error.name(), &error.message());
+                Some(finished)
+            },
+        }
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        T::out_signatures()
+    }
+}
+
+impl<T: MethodResult + 'static> MethodResult for Async<T> {
+    fn return_to(self, invocation: MethodInvocation) {
+        future::spawn_local(Completion {
+            finished: None,
+            future: self,
+            invocation: Some(invocation),
+        });
+    }
+
+    fn return_to_then<F: FnOnce() + 'static>(self, invocation: MethodInvocation, finished: F) -> Option<F> {
+        future::spawn_local(Completion {
+            finished: Some(Box::new(finished)),
+            future: self,
+            invocation: Some(invocation),
+        });
+        None
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        T::out_signatures()
+    }
+}
+
+impl<T: MethodResult + 'static> MethodResult for Reply<T> {
+    fn return_to(self, invocation: MethodInvocation) {
+        future::spawn_local(Completion {
+            finished: None,
+            future: self,
+            invocation: Some(invocation),
+        });
+    }
+
+    fn return_to_then<F: FnOnce() + 'static>(self, invocation: MethodInvocation, finished: F) -> Option<F> {
+        future::spawn_local(Completion {
+            finished: Some(Box::new(finished)),
+            future: self,
+            invocation: Some(invocation),
+        });
+        None
+    }
+
+    fn out_signatures() -> Vec<&'static str> {
+        T::out_signatures()
+    }
+}
+
+/// Future replying to the invocation with the output of `future` when it completes, then calling `finished`.
+struct Completion<F> {
+    finished: Option<Box<FnOnce()>>,
+    future: F,
+    invocation: Option<MethodInvocation>,
+}
+
+impl<F: Future + Unpin> Future for Completion<F> where F::Output: MethodResult {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        let completion = self.get_mut();
+        match Pin::new(&mut completion.future).poll(context) {
+            Poll::Ready(result) => {
+                if let Some(invocation) = completion.invocation.take() {
+                    match completion.finished.take() {
+                        Some(finished) => {
+                            if let Some(finished) = result.return_to_then(invocation, finished) {
+                                finished();
+                            }
+                        },
+                        None => result.return_to(invocation),
+                    }
+                }
+                Poll::Ready(())
+            },
+            Poll::Pending => Poll::Pending,
+        }
     }
 }
@@ -22,19 +22,341 @@
 //! D-Bus Introspection Data — Node and interface description data structures.
 
 use std::ffi::CString;
-use std::ptr::null_mut;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ptr::{null_mut, write};
 
-use gio_sys::{GDBusInterfaceInfo, GDBusNodeInfo, g_dbus_node_info_new_for_xml, g_dbus_node_info_unref};
+use gio_sys::{GDBusAnnotationInfo, GDBusArgInfo, GDBusInterfaceInfo, GDBusMethodInfo, GDBusNodeInfo, GDBusPropertyInfo, GDBusPropertyInfoFlags, GDBusSignalInfo, G_DBUS_ERROR_INVALID_ARGS, g_dbus_annotation_info_lookup, g_dbus_annotation_info_ref, g_dbus_annotation_info_unref, g_dbus_arg_info_ref, g_dbus_arg_info_unref, g_dbus_error_quark, g_dbus_interface_info_generate_xml, g_dbus_interface_info_lookup_method, g_dbus_interface_info_lookup_property, g_dbus_interface_info_lookup_signal, g_dbus_interface_info_ref, g_dbus_interface_info_unref, g_dbus_is_interface_name, g_dbus_is_member_name, g_dbus_method_info_ref, g_dbus_method_info_unref, g_dbus_node_info_generate_xml, g_dbus_node_info_lookup_interface, g_dbus_node_info_new_for_xml, g_dbus_node_info_ref, g_dbus_node_info_unref, g_dbus_property_info_ref, g_dbus_property_info_unref, g_dbus_signal_info_ref, g_dbus_signal_info_unref};
 use glib::Error;
-use glib::translate::from_glib_full;
+use glib::translate::{from_glib_full, from_glib_none};
+use glib_sys::{GString, GFALSE, Volatile, g_error_new_literal, g_malloc0, g_strdup, g_string_free, g_string_new, g_variant_is_object_path, g_variant_is_signature, g_variant_type_string_is_valid};
+use libc::{c_char, c_int, c_uint};
 
-/// Information about a D-Bus interface.
-pub struct InterfaceInfo(*mut GDBusInterfaceInfo);
+bitflags! {
+    /// Flags describing the access control of a D-Bus property.
+    pub flags PropertyInfoFlags: u32 {
+        /// No flags set.
+        const PROPERTY_INFO_FLAGS_NONE = 0,
+        /// Property is readable.
+        const PROPERTY_INFO_FLAGS_READABLE = 1,
+        /// Property is writable.
+        const PROPERTY_INFO_FLAGS_WRITABLE = 2,
+    }
+}
+
+/// Layout-compatible version of `GDBusPropertyInfo` used by `dbus_class!`.
+/// The flags are a plain integer so that a readable and writable property can be built in a constant.
+#[doc(hidden)]
+#[repr(C)]
+pub struct StaticPropertyInfo {
+    pub ref_count: Volatile<c_int>,
+    pub name: *mut c_char,
+    pub signature: *mut c_char,
+    pub flags: c_uint,
+    pub annotations: *mut *mut GDBusAnnotationInfo,
+}
+
+macro_rules! info_type {
+    ($(#[$attr:meta])* struct $name:ident($glib_type:ty), $reference:ident, $unreference:ident) => {
+        $(#[$attr])*
+        pub struct $name(*mut $glib_type);
+
+        impl $name {
+            /// Create from a pointer, taking a new reference on it.
+            pub fn new(info: *mut $glib_type) -> Self {
+                $name(unsafe { $reference(info) })
+            }
+
+            /// Looks up the value of the annotation named `name`.
+            pub fn annotation(&self, name: &str) -> Option<String> {
+                lookup_annotation(unsafe { (*self.0).annotations }, name)
+            }
+
+            /// Iterate over the annotations.
+            pub fn annotations(&self) -> Iter<AnnotationInfo> {
+                Iter::new(unsafe { (*self.0).annotations })
+            }
+
+            /// Convert to the sys type.
+            pub fn to_glib(&self) -> *mut $glib_type {
+                self.0
+            }
+        }
+
+        impl Clone for $name {
+            fn clone(&self) -> Self {
+                $name::new(self.0)
+            }
+        }
+
+        impl Drop for $name {
+            fn drop(&mut self) {
+                unsafe { $unreference(self.0) };
+            }
+        }
+
+        #[doc(hidden)]
+        impl Info for $name {
+            type GlibType = $glib_type;
+
+            fn from_glib(info: *mut Self::GlibType) -> Self {
+                $name::new(info)
+            }
+        }
+    };
+}
+
+/// Trait for introspection data types that can be created from a pointer to their sys type.
+#[doc(hidden)]
+pub trait Info {
+    /// The sys type.
+    type GlibType;
+
+    /// Create the value from a pointer, taking a new reference on it.
+    fn from_glib(info: *mut Self::GlibType) -> Self;
+}
+
+/// Iterator over a `NULL`-terminated array of introspection data.
+pub struct Iter<'a, T: Info> {
+    array: *mut *mut T::GlibType,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T: Info> Iter<'a, T> {
+    fn new(array: *mut *mut T::GlibType) -> Self {
+        Iter {
+            array: array,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: Info> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.array.is_null() || unsafe { (*self.array).is_null() } {
+            None
+        }
+        else {
+            let info = unsafe { *self.array };
+            self.array = unsafe { self.array.offset(1) };
+            Some(T::from_glib(info))
+        }
+    }
+}
+
+fn lookup_annotation(annotations: *mut *mut GDBusAnnotationInfo, name: &str) -> Option<String> {
+    let name = CString::new(name).unwrap();
+    unsafe { from_glib_none(g_dbus_annotation_info_lookup(annotations, name.as_ptr())) }
+}
+
+fn lookup<T: Info>(info: *mut T::GlibType) -> Option<T> {
+    if info.is_null() {
+        None
+    }
+    else {
+        Some(T::from_glib(info))
+    }
+}
+
+info_type! {
+    /// Information about an annotation.
+    struct AnnotationInfo(GDBusAnnotationInfo), g_dbus_annotation_info_ref, g_dbus_annotation_info_unref
+}
+
+impl AnnotationInfo {
+    /// The name of the annotation, e.g. `org.freedesktop.DBus.Deprecated`.
+    pub fn key(&self) -> String {
+        unsafe { from_glib_none((*self.0).key) }
+    }
+
+    /// The value of the annotation.
+    pub fn value(&self) -> String {
+        unsafe { from_glib_none((*self.0).value) }
+    }
+}
+
+info_type! {
+    /// Information about an argument for a method or a signal.
+    struct ArgInfo(GDBusArgInfo), g_dbus_arg_info_ref, g_dbus_arg_info_unref
+}
+
+impl ArgInfo {
+    /// Name of the argument, e.g. `unix_user_id`.
+    pub fn name(&self) -> String {
+        unsafe { from_glib_none((*self.0).name) }
+    }
+
+    /// D-Bus signature of the argument (a single complete type).
+    pub fn signature(&self) -> String {
+        unsafe { from_glib_none((*self.0).signature) }
+    }
+}
+
+info_type! {
+    /// Information about a method on a D-Bus interface.
+    struct MethodInfo(GDBusMethodInfo), g_dbus_method_info_ref, g_dbus_method_info_unref
+}
+
+impl MethodInfo {
+    /// Start describing a method named `name`, to be added with `InterfaceInfoBuilder::method()`.
+    pub fn builder(name: &str) -> MethodInfoBuilder {
+        MethodInfoBuilder {
+            annotations: vec![],
+            in_args: vec![],
+            name: name.to_string(),
+            out_args: vec![],
+        }
+    }
+
+    /// Iterate over the in arguments.
+    pub fn in_args(&self) -> Iter<ArgInfo> {
+        Iter::new(unsafe { (*self.0).in_args })
+    }
+
+    /// The signature of the tuple of in arguments, e.g. `(xs)`.
+    pub fn in_signature(&self) -> String {
+        tuple_signature(self.in_args())
+    }
+
+    /// The name of the D-Bus method, e.g. `RequestName`.
+    pub fn name(&self) -> String {
+        unsafe { from_glib_none((*self.0).name) }
+    }
+
+    /// Iterate over the out arguments.
+    pub fn out_args(&self) -> Iter<ArgInfo> {
+        Iter::new(unsafe { (*self.0).out_args })
+    }
+
+    /// The signature of the tuple of out arguments, e.g. `(xy)`.
+    pub fn out_signature(&self) -> String {
+        tuple_signature(self.out_args())
+    }
+}
+
+info_type! {
+    /// Information about a signal on a D-Bus interface.
+    struct SignalInfo(GDBusSignalInfo), g_dbus_signal_info_ref, g_dbus_signal_info_unref
+}
+
+impl SignalInfo {
+    /// Iterate over the arguments.
+    pub fn args(&self) -> Iter<ArgInfo> {
+        Iter::new(unsafe { (*self.0).args })
+    }
+
+    /// Start describing a signal named `name`, to be added with `InterfaceInfoBuilder::signal()`.
+    pub fn builder(name: &str) -> SignalInfoBuilder {
+        SignalInfoBuilder {
+            annotations: vec![],
+            args: vec![],
+            name: name.to_string(),
+        }
+    }
+
+    /// The name of the D-Bus signal, e.g. `NameOwnerChanged`.
+    pub fn name(&self) -> String {
+        unsafe { from_glib_none((*self.0).name) }
+    }
+
+    /// The signature of the tuple of arguments, e.g. `(sss)`.
+    pub fn signature(&self) -> String {
+        tuple_signature(self.args())
+    }
+}
+
+info_type! {
+    /// Information about a D-Bus property on a D-Bus interface.
+    struct PropertyInfo(GDBusPropertyInfo), g_dbus_property_info_ref, g_dbus_property_info_unref
+}
+
+impl PropertyInfo {
+    /// Start describing a property named `name` of type `signature`, to be added with `InterfaceInfoBuilder::property()`.
+    pub fn builder(name: &str, signature: &str, flags: PropertyInfoFlags) -> PropertyInfoBuilder {
+        PropertyInfoBuilder {
+            annotations: vec![],
+            flags: flags,
+            name: name.to_string(),
+            signature: signature.to_string(),
+        }
+    }
+
+    /// Access control flags for the property.
+    pub fn flags(&self) -> PropertyInfoFlags {
+        PropertyInfoFlags::from_bits_truncate(unsafe { (*self.0).flags.bits() })
+    }
+
+    /// The name of the D-Bus property, e.g. `SupportedFilesystems`.
+    pub fn name(&self) -> String {
+        unsafe { from_glib_none((*self.0).name) }
+    }
+
+    /// The D-Bus signature of the property (a single complete type).
+    pub fn signature(&self) -> String {
+        unsafe { from_glib_none((*self.0).signature) }
+    }
+}
+
+info_type! {
+    /// Information about a D-Bus interface.
+    struct InterfaceInfo(GDBusInterfaceInfo), g_dbus_interface_info_ref, g_dbus_interface_info_unref
+}
 
 impl InterfaceInfo {
-    /// Convert to the sys type.
-    pub fn to_glib(&self) -> *mut GDBusInterfaceInfo {
-        self.0
+    /// Start describing an interface named `name` at runtime.
+    pub fn builder(name: &str) -> InterfaceInfoBuilder {
+        InterfaceInfoBuilder {
+            annotations: vec![],
+            methods: vec![],
+            name: name.to_string(),
+            properties: vec![],
+            signals: vec![],
+        }
+    }
+
+    /// Looks up information about a method.
+    pub fn lookup_method(&self, name: &str) -> Option<MethodInfo> {
+        let name = CString::new(name).unwrap();
+        lookup(unsafe { g_dbus_interface_info_lookup_method(self.0, name.as_ptr()) })
+    }
+
+    /// Looks up information about a property.
+    pub fn lookup_property(&self, name: &str) -> Option<PropertyInfo> {
+        let name = CString::new(name).unwrap();
+        lookup(unsafe { g_dbus_interface_info_lookup_property(self.0, name.as_ptr()) })
+    }
+
+    /// Looks up information about a signal.
+    pub fn lookup_signal(&self, name: &str) -> Option<SignalInfo> {
+        let name = CString::new(name).unwrap();
+        lookup(unsafe { g_dbus_interface_info_lookup_signal(self.0, name.as_ptr()) })
+    }
+
+    /// Iterate over the methods of the interface.
+    pub fn methods(&self) -> Iter<MethodInfo> {
+        Iter::new(unsafe { (*self.0).methods })
+    }
+
+    /// The name of the D-Bus interface, e.g. `org.freedesktop.DBus.Properties`.
+    pub fn name(&self) -> String {
+        unsafe { from_glib_none((*self.0).name) }
+    }
+
+    /// Iterate over the properties of the interface.
+    pub fn properties(&self) -> Iter<PropertyInfo> {
+        Iter::new(unsafe { (*self.0).properties })
+    }
+
+    /// Iterate over the signals of the interface.
+    pub fn signals(&self) -> Iter<SignalInfo> {
+        Iter::new(unsafe { (*self.0).signals })
+    }
+
+    /// Generates the introspection XML for the interface, i.e. its `<interface>` element.
+    pub fn to_xml(&self) -> String {
+        generate_xml(|string_builder| unsafe { g_dbus_interface_info_generate_xml(self.0, 0, string_builder) })
     }
 }
 
@@ -47,6 +369,16 @@ impl NodeInfo {
         NodeInfo(node_info)
     }
 
+    /// Start describing a node at runtime.
+    pub fn builder() -> NodeInfoBuilder {
+        NodeInfoBuilder {
+            annotations: vec![],
+            interfaces: vec![],
+            nodes: vec![],
+            path: None,
+        }
+    }
+
     /// Parses `xml_data` and returns a `NodeInfo` representing the data.
     /// The introspection XML must contain exactly one top-level <node> element.
     /// Note that this routine is using a GMarkup-based parser that only accepts a subset of valid XML documents.
@@ -62,9 +394,54 @@ impl NodeInfo {
         }
     }
 
+    /// Looks up the value of the annotation named `name`.
+    pub fn annotation(&self, name: &str) -> Option<String> {
+        lookup_annotation(unsafe { (*self.0).annotations }, name)
+    }
+
+    /// Iterate over the annotations.
+    pub fn annotations(&self) -> Iter<AnnotationInfo> {
+        Iter::new(unsafe { (*self.0).annotations })
+    }
+
     /// Return an interface from the node by its `index`.
-    pub fn interface(&self, index: isize) -> InterfaceInfo {
-        InterfaceInfo(unsafe { *(*self.0).interfaces.offset(index) })
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn interface(&self, index: usize) -> InterfaceInfo {
+        self.interfaces().nth(index).expect("interface index out of bounds")
+    }
+
+    /// Iterate over the interfaces of the node.
+    pub fn interfaces(&self) -> Iter<InterfaceInfo> {
+        Iter::new(unsafe { (*self.0).interfaces })
+    }
+
+    /// Looks up information about an interface.
+    pub fn lookup_interface(&self, name: &str) -> Option<InterfaceInfo> {
+        let name = CString::new(name).unwrap();
+        lookup(unsafe { g_dbus_node_info_lookup_interface(self.0, name.as_ptr()) })
+    }
+
+    /// Iterate over the child nodes.
+    pub fn nodes(&self) -> Iter<NodeInfo> {
+        Iter::new(unsafe { (*self.0).nodes })
+    }
+
+    /// The path of the node or `None` if omitted. Note that this may be a relative path.
+    pub fn path(&self) -> Option<String> {
+        unsafe { from_glib_none((*self.0).path) }
+    }
+
+    /// Convert to the sys type.
+    pub fn to_glib(&self) -> *mut GDBusNodeInfo {
+        self.0
+    }
+
+    /// Generates the introspection XML for the node, i.e. a `<node>` element containing its interfaces and child nodes.
+    pub fn to_xml(&self) -> String {
+        generate_xml(|string_builder| unsafe { g_dbus_node_info_generate_xml(self.0, 0, string_builder) })
     }
 }
 
@@ -73,3 +450,370 @@ impl Drop for NodeInfo {
         unsafe { g_dbus_node_info_unref(self.0) };
     }
 }
+
+#[doc(hidden)]
+impl Info for NodeInfo {
+    type GlibType = GDBusNodeInfo;
+
+    fn from_glib(info: *mut Self::GlibType) -> Self {
+        NodeInfo(unsafe { g_dbus_node_info_ref(info) })
+    }
+}
+
+/// `MethodInfo` builder.
+pub struct MethodInfoBuilder {
+    annotations: Vec<(String, String)>,
+    in_args: Vec<(String, String)>,
+    name: String,
+    out_args: Vec<(String, String)>,
+}
+
+impl MethodInfoBuilder {
+    /// Add an annotation to the method.
+    pub fn annotation(mut self, name: &str, value: &str) -> Self {
+        self.annotations.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add an in argument named `name` of type `signature`.
+    pub fn in_arg(mut self, name: &str, signature: &str) -> Self {
+        self.in_args.push((name.to_string(), signature.to_string()));
+        self
+    }
+
+    /// Add an out argument named `name` of type `signature`.
+    pub fn out_arg(mut self, name: &str, signature: &str) -> Self {
+        self.out_args.push((name.to_string(), signature.to_string()));
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        try!(validate_member_name("method", &self.name));
+        try!(validate_args(&self.name, &self.in_args));
+        try!(validate_args(&self.name, &self.out_args));
+        validate_annotations(&self.name, &self.annotations)
+    }
+
+    unsafe fn to_glib(&self) -> *mut GDBusMethodInfo {
+        new_info(GDBusMethodInfo {
+            ref_count: Volatile(1),
+            name: new_string(&self.name),
+            in_args: new_args(&self.in_args),
+            out_args: new_args(&self.out_args),
+            annotations: new_annotations(&self.annotations),
+        })
+    }
+}
+
+/// `SignalInfo` builder.
+pub struct SignalInfoBuilder {
+    annotations: Vec<(String, String)>,
+    args: Vec<(String, String)>,
+    name: String,
+}
+
+impl SignalInfoBuilder {
+    /// Add an annotation to the signal.
+    pub fn annotation(mut self, name: &str, value: &str) -> Self {
+        self.annotations.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Add an argument named `name` of type `signature`.
+    pub fn arg(mut self, name: &str, signature: &str) -> Self {
+        self.args.push((name.to_string(), signature.to_string()));
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        try!(validate_member_name("signal", &self.name));
+        try!(validate_args(&self.name, &self.args));
+        validate_annotations(&self.name, &self.annotations)
+    }
+
+    unsafe fn to_glib(&self) -> *mut GDBusSignalInfo {
+        new_info(GDBusSignalInfo {
+            ref_count: Volatile(1),
+            name: new_string(&self.name),
+            args: new_args(&self.args),
+            annotations: new_annotations(&self.annotations),
+        })
+    }
+}
+
+/// `PropertyInfo` builder.
+pub struct PropertyInfoBuilder {
+    annotations: Vec<(String, String)>,
+    flags: PropertyInfoFlags,
+    name: String,
+    signature: String,
+}
+
+impl PropertyInfoBuilder {
+    /// Add an annotation to the property.
+    pub fn annotation(mut self, name: &str, value: &str) -> Self {
+        self.annotations.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        try!(validate_member_name("property", &self.name));
+        try!(validate_signature(&self.name, &self.signature));
+        validate_annotations(&self.name, &self.annotations)
+    }
+
+    unsafe fn to_glib(&self) -> *mut GDBusPropertyInfo {
+        new_info(GDBusPropertyInfo {
+            ref_count: Volatile(1),
+            name: new_string(&self.name),
+            signature: new_string(&self.signature),
+            flags: GDBusPropertyInfoFlags::from_bits_truncate(self.flags.bits()),
+            annotations: new_annotations(&self.annotations),
+        })
+    }
+}
+
+/// `InterfaceInfo` builder.
+pub struct InterfaceInfoBuilder {
+    annotations: Vec<(String, String)>,
+    methods: Vec<MethodInfoBuilder>,
+    name: String,
+    properties: Vec<PropertyInfoBuilder>,
+    signals: Vec<SignalInfoBuilder>,
+}
+
+impl InterfaceInfoBuilder {
+    /// Add an annotation to the interface.
+    pub fn annotation(mut self, name: &str, value: &str) -> Self {
+        self.annotations.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Validate the description and create the interface.
+    /// Returns an `org.freedesktop.DBus.Error.InvalidArgs` error if a name or a signature is invalid, or if a member is declared twice.
+    pub fn build(self) -> Result<InterfaceInfo, Error> {
+        let name = CString::new(self.name.as_str()).unwrap();
+        if unsafe { g_dbus_is_interface_name(name.as_ptr()) } == GFALSE {
+            return Err(invalid_args(&format!("'{}' is not a valid interface name", self.name)));
+        }
+        for (index, method) in self.methods.iter().enumerate() {
+            try!(method.validate());
+            try!(validate_unique("method", &method.name, self.methods[..index].iter().map(|method| &method.name)));
+        }
+        for (index, signal) in self.signals.iter().enumerate() {
+            try!(signal.validate());
+            try!(validate_unique("signal", &signal.name, self.signals[..index].iter().map(|signal| &signal.name)));
+        }
+        for (index, property) in self.properties.iter().enumerate() {
+            try!(property.validate());
+            try!(validate_unique("property", &property.name, self.properties[..index].iter().map(|property| &property.name)));
+        }
+        try!(validate_annotations(&self.name, &self.annotations));
+
+        let interface_info =
+            unsafe {
+                new_info(GDBusInterfaceInfo {
+                    ref_count: Volatile(1),
+                    name: new_string(&self.name),
+                    methods: new_array(self.methods.iter().map(|method| method.to_glib()).collect()),
+                    signals: new_array(self.signals.iter().map(|signal| signal.to_glib()).collect()),
+                    properties: new_array(self.properties.iter().map(|property| property.to_glib()).collect()),
+                    annotations: new_annotations(&self.annotations),
+                })
+            };
+        Ok(InterfaceInfo(interface_info))
+    }
+
+    /// Add a method to the interface.
+    pub fn method(mut self, method: MethodInfoBuilder) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    /// Add a property to the interface.
+    pub fn property(mut self, property: PropertyInfoBuilder) -> Self {
+        self.properties.push(property);
+        self
+    }
+
+    /// Add a signal to the interface.
+    pub fn signal(mut self, signal: SignalInfoBuilder) -> Self {
+        self.signals.push(signal);
+        self
+    }
+}
+
+/// `NodeInfo` builder.
+pub struct NodeInfoBuilder {
+    annotations: Vec<(String, String)>,
+    interfaces: Vec<InterfaceInfo>,
+    nodes: Vec<NodeInfo>,
+    path: Option<String>,
+}
+
+impl NodeInfoBuilder {
+    /// Add an annotation to the node.
+    pub fn annotation(mut self, name: &str, value: &str) -> Self {
+        self.annotations.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Validate the description and create the node.
+    /// Returns an `org.freedesktop.DBus.Error.InvalidArgs` error if the path is invalid, or if an interface or a child node is declared twice.
+    pub fn build(self) -> Result<NodeInfo, Error> {
+        if let Some(ref path) = self.path {
+            let absolute_path =
+                if path.starts_with('/') {
+                    path.clone()
+                }
+                else {
+                    format!("/{}", path)
+                };
+            let absolute_path = CString::new(absolute_path).unwrap();
+            if unsafe { g_variant_is_object_path(absolute_path.as_ptr()) } == GFALSE {
+                return Err(invalid_args(&format!("'{}' is not a valid object path", path)));
+            }
+        }
+        let interface_names: Vec<_> = self.interfaces.iter().map(|interface| interface.name()).collect();
+        for (index, name) in interface_names.iter().enumerate() {
+            try!(validate_unique("interface", name, interface_names[..index].iter()));
+        }
+        let node_paths: Vec<_> = self.nodes.iter().filter_map(|node| node.path()).collect();
+        for (index, path) in node_paths.iter().enumerate() {
+            try!(validate_unique("node", path, node_paths[..index].iter()));
+        }
+        try!(validate_annotations("node", &self.annotations));
+
+        let node_info =
+            unsafe {
+                new_info(GDBusNodeInfo {
+                    ref_count: Volatile(1),
+                    path: self.path.as_ref().map(|path| new_string(path)).unwrap_or(null_mut()),
+                    interfaces: new_array(self.interfaces.iter().map(|interface| g_dbus_interface_info_ref(interface.to_glib())).collect()),
+                    nodes: new_array(self.nodes.iter().map(|node| g_dbus_node_info_ref(node.to_glib())).collect()),
+                    annotations: new_annotations(&self.annotations),
+                })
+            };
+        Ok(NodeInfo(node_info))
+    }
+
+    /// Add an interface to the node.
+    pub fn interface(mut self, interface: InterfaceInfo) -> Self {
+        self.interfaces.push(interface);
+        self
+    }
+
+    /// Add a child node.
+    pub fn node(mut self, node: NodeInfo) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    /// Set the path of the node. Child nodes usually have a path relative to their parent.
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+}
+
+fn invalid_args(message: &str) -> Error {
+    let message = CString::new(message).unwrap();
+    unsafe { from_glib_full(g_error_new_literal(g_dbus_error_quark(), G_DBUS_ERROR_INVALID_ARGS as i32, message.as_ptr())) }
+}
+
+unsafe fn new_annotations(annotations: &[(String, String)]) -> *mut *mut GDBusAnnotationInfo {
+    new_array(annotations.iter()
+        .map(|&(ref name, ref value)| new_info(GDBusAnnotationInfo {
+            ref_count: Volatile(1),
+            key: new_string(name),
+            value: new_string(value),
+            annotations: null_mut(),
+        }))
+        .collect())
+}
+
+unsafe fn new_args(args: &[(String, String)]) -> *mut *mut GDBusArgInfo {
+    new_array(args.iter()
+        .map(|&(ref name, ref signature)| new_info(GDBusArgInfo {
+            ref_count: Volatile(1),
+            name: new_string(name),
+            signature: new_string(signature),
+            annotations: null_mut(),
+        }))
+        .collect())
+}
+
+/// Allocate a `NULL`-terminated array with the GLib allocator, so that GDBus can free it.
+unsafe fn new_array<T>(items: Vec<*mut T>) -> *mut *mut T {
+    let array = g_malloc0((items.len() + 1) * size_of::<*mut T>()) as *mut *mut T;
+    for (index, item) in items.into_iter().enumerate() {
+        *array.offset(index as isize) = item;
+    }
+    array
+}
+
+/// Allocate `info` with the GLib allocator, so that GDBus can free it.
+unsafe fn new_info<T>(info: T) -> *mut T {
+    let pointer = g_malloc0(size_of::<T>()) as *mut T;
+    write(pointer, info);
+    pointer
+}
+
+unsafe fn new_string(string: &str) -> *mut c_char {
+    let string = CString::new(string).unwrap();
+    g_strdup(string.as_ptr())
+}
+
+fn validate_annotations(owner: &str, annotations: &[(String, String)]) -> Result<(), Error> {
+    for &(ref name, _) in annotations {
+        if name.is_empty() {
+            return Err(invalid_args(&format!("empty annotation name on '{}'", owner)));
+        }
+    }
+    Ok(())
+}
+
+fn validate_args(member: &str, args: &[(String, String)]) -> Result<(), Error> {
+    for &(_, ref signature) in args {
+        try!(validate_signature(member, signature));
+    }
+    Ok(())
+}
+
+fn validate_member_name(kind: &str, name: &str) -> Result<(), Error> {
+    let c_name = CString::new(name).unwrap();
+    if unsafe { g_dbus_is_member_name(c_name.as_ptr()) } == GFALSE {
+        return Err(invalid_args(&format!("'{}' is not a valid {} name", name, kind)));
+    }
+    Ok(())
+}
+
+/// Check that `signature` is a single complete type.
+fn validate_signature(member: &str, signature: &str) -> Result<(), Error> {
+    let c_signature = CString::new(signature).unwrap();
+    let valid = unsafe { g_variant_is_signature(c_signature.as_ptr()) != GFALSE && g_variant_type_string_is_valid(c_signature.as_ptr()) != GFALSE };
+    if !valid {
+        return Err(invalid_args(&format!("'{}' is not a single complete type in '{}'", signature, member)));
+    }
+    Ok(())
+}
+
+fn validate_unique<'a, I: Iterator<Item=&'a String>>(kind: &str, name: &str, mut previous_names: I) -> Result<(), Error> {
+    if previous_names.any(|previous_name| previous_name == name) {
+        return Err(invalid_args(&format!("{} '{}' is declared more than once", kind, name)));
+    }
+    Ok(())
+}
+
+fn generate_xml<F: FnOnce(*mut GString)>(generate: F) -> String {
+    unsafe {
+        let string_builder = g_string_new(null_mut());
+        generate(string_builder);
+        from_glib_full(g_string_free(string_builder, GFALSE))
+    }
+}
+
+fn tuple_signature(args: Iter<ArgInfo>) -> String {
+    let signatures: String = args.map(|arg| arg.signature()).collect();
+    format!("({})", signatures)
+}
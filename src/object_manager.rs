@@ -0,0 +1,483 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! Server and client of the `org.freedesktop.DBus.ObjectManager` interface.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{CStr, CString};
+use std::ptr::null_mut;
+use std::rc::Rc;
+
+use gio_sys::{G_DBUS_ERROR_INVALID_ARGS, g_dbus_error_quark};
+use glib::error::Error;
+use glib::translate::from_glib_full;
+use glib_sys::{GVariant, g_error_new_literal, g_variant_get_string, g_variant_get_variant, g_variant_new_array, g_variant_new_dict_entry, g_variant_new_object_path, g_variant_new_string, g_variant_new_strv, g_variant_new_tuple, g_variant_new_variant, g_variant_type_free, g_variant_type_new};
+
+use connection::{Connection, SignalSubscription, SEND_MESSAGE_FLAGS_NONE, SIGNAL_FLAGS_NONE};
+use interface::{Interface, InterfaceProxy};
+use message::Message;
+use node_info::{InterfaceInfo, MethodInfo, SignalInfo};
+use variant::Variant;
+
+/// Name of the object manager interface.
+pub const OBJECT_MANAGER_INTERFACE: &'static str = "org.freedesktop.DBus.ObjectManager";
+
+/// The interfaces of an object, with the values of their properties.
+pub type InterfacesAndProperties = Vec<(String, Vec<(String, Variant)>)>;
+
+/// An object which can be exported by an `ObjectManagerServer`.
+/// It is implemented for the types generated by `#[interface]` (in a `Rc<RefCell<_>>`) and by `dbus_class!`.
+pub trait ManagedObject {
+    /// Register the interfaces of the object at `object_path`.
+    /// Returns the registration ids.
+    fn register(&self, connection: &Connection, object_path: &str) -> Result<Vec<u32>, Error>;
+
+    /// Get the name of every interface of the object, with the values of its readable properties.
+    fn interfaces(&self) -> InterfacesAndProperties;
+}
+
+impl<T: Interface> ManagedObject for Rc<RefCell<T>> {
+    fn register(&self, connection: &Connection, object_path: &str) -> Result<Vec<u32>, Error> {
+        connection.register_interface(object_path, self).map(|id| vec![id])
+    }
+
+    fn interfaces(&self) -> InterfacesAndProperties {
+        vec![(T::INTERFACE_NAME.to_string(), self.borrow().property_values())]
+    }
+}
+
+struct ExportedObject {
+    object: Box<ManagedObject>,
+    object_path: String,
+    registration_ids: Vec<u32>,
+}
+
+/// Exports objects under a root path and implements `org.freedesktop.DBus.ObjectManager` on this path.
+/// `InterfacesAdded` and `InterfacesRemoved` are emitted when an object is exported and unexported.
+/// The objects are unexported when the server is dropped.
+pub struct ObjectManagerServer {
+    connection: Connection,
+    object_path: String,
+    objects: Rc<RefCell<Vec<ExportedObject>>>,
+    registration_id: u32,
+}
+
+impl ObjectManagerServer {
+    /// Create a new object manager at `object_path`, which is the root of the exported objects.
+    pub fn new(connection: &Connection, object_path: &str) -> Result<Self, Error> {
+        let interface = try!(object_manager_interface());
+        let objects: Rc<RefCell<Vec<ExportedObject>>> = Rc::new(RefCell::new(vec![]));
+        let method_objects = objects.clone();
+        let registration_id = try!(connection.register_object(object_path, interface, move |method_name, _args, invocation| {
+            match method_name {
+                "GetManagedObjects" => {
+                    let objects: Vec<_> = method_objects.borrow().iter()
+                        .map(|object| (object.object_path.clone(), object.object.interfaces()))
+                        .collect();
+                    invocation.return_value(managed_objects_variant(objects));
+                },
+                _ => invocation.return_dbus_error("org.freedesktop.DBus.Error.UnknownMethod", &format!("Unknown method {}", method_name)),
+            }
+        }));
+        Ok(ObjectManagerServer {
+            connection: connection.clone(),
+            object_path: object_path.to_string(),
+            objects: objects,
+            registration_id: registration_id,
+        })
+    }
+
+    /// Get the connection on which the objects are exported.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Export `object` at `object_path`, which must be below the root path of the manager.
+    /// A `dbus_class!` instance can only be exported when it has a single object, which is exported at `object_path` instead of its own path, and when it is not running.
+    pub fn export<O: ManagedObject + 'static>(&self, object_path: &str, object: O) -> Result<(), Error> {
+        if !self.is_managed_path(object_path) {
+            let message = format!("Object path '{}' is not below '{}'", object_path, self.object_path);
+            return Err(invalid_args_error(&message));
+        }
+        if self.is_exported(object_path) {
+            let message = format!("An object is already exported at '{}'", object_path);
+            return Err(invalid_args_error(&message));
+        }
+        let registration_ids = try!(object.register(&self.connection, object_path));
+        let interfaces = object.interfaces();
+        self.objects.borrow_mut().push(ExportedObject {
+            object: Box::new(object),
+            object_path: object_path.to_string(),
+            registration_ids: registration_ids,
+        });
+        let parameters = unsafe {
+            let object_path = CString::new(object_path).unwrap();
+            let children = [g_variant_new_object_path(object_path.as_ptr()), interfaces_variant(interfaces)];
+            g_variant_new_tuple(children.as_ptr(), children.len())
+        };
+        self.connection.emit_signal(None, &self.object_path, OBJECT_MANAGER_INTERFACE, "InterfacesAdded", Variant::new(parameters))
+    }
+
+    /// Check whether an object is exported at `object_path`.
+    pub fn is_exported(&self, object_path: &str) -> bool {
+        self.objects.borrow().iter().any(|object| object.object_path == object_path)
+    }
+
+    /// Get the root path of the exported objects.
+    pub fn object_path(&self) -> &str {
+        &self.object_path
+    }
+
+    /// Stop exporting the object at `object_path`.
+    /// Returns `false` if there is no object at this path.
+    pub fn unexport(&self, object_path: &str) -> bool {
+        let position = self.objects.borrow().iter().position(|object| object.object_path == object_path);
+        match position {
+            Some(position) => {
+                let object = self.objects.borrow_mut().remove(position);
+                self.unregister(object);
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn is_managed_path(&self, object_path: &str) -> bool {
+        let prefix =
+            if self.object_path == "/" {
+                "/".to_string()
+            }
+            else {
+                format!("{}/", self.object_path)
+            };
+        object_path.len() > prefix.len() && object_path.starts_with(&prefix)
+    }
+
+    fn unregister(&self, object: ExportedObject) {
+        for &id in &object.registration_ids {
+            self.connection.unregister_object(id);
+        }
+        let interfaces: Vec<_> = object.object.interfaces().into_iter()
+            .map(|(name, _)| CString::new(name).unwrap())
+            .collect();
+        let interfaces: Vec<_> = interfaces.iter().map(|name| name.as_ptr()).collect();
+        let parameters = unsafe {
+            let object_path = CString::new(object.object_path).unwrap();
+            let children = [g_variant_new_object_path(object_path.as_ptr()), g_variant_new_strv(interfaces.as_ptr(), interfaces.len() as isize)];
+            g_variant_new_tuple(children.as_ptr(), children.len())
+        };
+        self.connection.emit_signal(None, &self.object_path, OBJECT_MANAGER_INTERFACE, "InterfacesRemoved", Variant::new(parameters)).ok();
+    }
+}
+
+impl Drop for ObjectManagerServer {
+    fn drop(&mut self) {
+        let objects: Vec<_> = self.objects.borrow_mut().drain(..).collect();
+        for object in objects {
+            self.unregister(object);
+        }
+        self.connection.unregister_object(self.registration_id);
+    }
+}
+
+/// The interfaces of a remote object, with the values of their properties.
+pub type ObjectInterfaces = HashMap<String, HashMap<String, Variant>>;
+
+struct ClientState {
+    object_added_callback: Option<Box<Fn(&str, &ObjectInterfaces)>>,
+    object_removed_callback: Option<Box<Fn(&str)>>,
+    objects: RefCell<BTreeMap<String, ObjectInterfaces>>,
+}
+
+impl ClientState {
+    fn add_interfaces(&self, object_path: &str, interfaces: ObjectInterfaces) {
+        let added = {
+            let mut objects = self.objects.borrow_mut();
+            let is_new = !objects.contains_key(object_path);
+            let object = objects.entry(object_path.to_string()).or_insert_with(HashMap::new);
+            object.extend(interfaces);
+            if is_new {
+                Some(object.clone())
+            }
+            else {
+                None
+            }
+        };
+        if let (Some(interfaces), Some(callback)) = (added, self.object_added_callback.as_ref()) {
+            callback(object_path, &interfaces);
+        }
+    }
+
+    fn remove_interfaces(&self, object_path: &str, interface_names: &[String]) {
+        let removed = {
+            let mut objects = self.objects.borrow_mut();
+            let is_empty =
+                match objects.get_mut(object_path) {
+                    Some(object) => {
+                        for interface_name in interface_names {
+                            object.remove(interface_name);
+                        }
+                        object.is_empty()
+                    },
+                    None => false,
+                };
+            if is_empty {
+                objects.remove(object_path);
+            }
+            is_empty
+        };
+        if let (true, Some(callback)) = (removed, self.object_removed_callback.as_ref()) {
+            callback(object_path);
+        }
+    }
+
+    fn update_properties(&self, object_path: &str, parameters: &Variant) {
+        let mut objects = self.objects.borrow_mut();
+        let interface_name = variant_string(&parameters.child_value(0));
+        if let Some(properties) = objects.get_mut(object_path).and_then(|object| object.get_mut(&interface_name)) {
+            properties.extend(parse_properties(&parameters.child_value(1)));
+            for name in parse_strings(&parameters.child_value(2)) {
+                properties.remove(&name);
+            }
+        }
+    }
+}
+
+/// Client of a remote `org.freedesktop.DBus.ObjectManager`.
+/// It keeps a cache of the managed objects, with their interfaces and properties, which is updated by the `InterfacesAdded`, `InterfacesRemoved` and `PropertiesChanged` signals.
+pub struct ObjectManagerClient {
+    connection: Connection,
+    dbus_name: String,
+    state: Rc<ClientState>,
+    _subscriptions: Vec<SignalSubscription>,
+}
+
+impl ObjectManagerClient {
+    /// Create a client for the object manager at `object_path` owned by `dbus_name`.
+    pub fn new(connection: &Connection, dbus_name: &str, object_path: &str) -> ObjectManagerClientBuilder {
+        ObjectManagerClientBuilder {
+            connection: connection.clone(),
+            dbus_name: dbus_name.to_string(),
+            object_added_callback: None,
+            object_path: object_path.to_string(),
+            object_removed_callback: None,
+        }
+    }
+
+    /// Get the connection used by the client.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Check whether the object at `object_path` implements `interface_name`.
+    pub fn has_interface(&self, object_path: &str, interface_name: &str) -> bool {
+        self.state.objects.borrow().get(object_path).map(|object| object.contains_key(interface_name)).unwrap_or(false)
+    }
+
+    /// Get the interfaces of the object at `object_path`, or `None` if there is no such object.
+    pub fn interfaces(&self, object_path: &str) -> Option<ObjectInterfaces> {
+        self.state.objects.borrow().get(object_path).cloned()
+    }
+
+    /// Get the paths of the managed objects, in sorted order.
+    pub fn object_paths(&self) -> Vec<String> {
+        self.state.objects.borrow().keys().cloned().collect()
+    }
+
+    /// Get the cached value of a property.
+    pub fn property(&self, object_path: &str, interface_name: &str, property_name: &str) -> Option<Variant> {
+        self.state.objects.borrow().get(object_path)
+            .and_then(|object| object.get(interface_name))
+            .and_then(|properties| properties.get(property_name))
+            .cloned()
+    }
+
+    /// Create a proxy, generated by `dbus_interface!` or `#[proxy]`, for the object at `object_path`.
+    /// Returns `None` if the object does not implement the interface of the proxy.
+    pub fn proxy<P: InterfaceProxy>(&self, object_path: &str) -> Option<P> {
+        if self.has_interface(object_path, P::INTERFACE_NAME) {
+            Some(P::new_for_connection(&self.connection, &self.dbus_name, object_path))
+        }
+        else {
+            None
+        }
+    }
+
+    /// Create a proxy for every object implementing the interface of the proxy.
+    pub fn proxies<P: InterfaceProxy>(&self) -> Vec<(String, P)> {
+        self.state.objects.borrow().iter()
+            .filter(|&(_, object)| object.contains_key(P::INTERFACE_NAME))
+            .map(|(object_path, _)| (object_path.clone(), P::new_for_connection(&self.connection, &self.dbus_name, object_path)))
+            .collect()
+    }
+}
+
+/// `ObjectManagerClient` builder.
+pub struct ObjectManagerClientBuilder {
+    connection: Connection,
+    dbus_name: String,
+    object_added_callback: Option<Box<Fn(&str, &ObjectInterfaces)>>,
+    object_path: String,
+    object_removed_callback: Option<Box<Fn(&str)>>,
+}
+
+impl ObjectManagerClientBuilder {
+    /// Create the client and fill its cache by calling `GetManagedObjects`.
+    pub fn build(self) -> Result<ObjectManagerClient, Error> {
+        let state = Rc::new(ClientState {
+            object_added_callback: self.object_added_callback,
+            object_removed_callback: self.object_removed_callback,
+            objects: RefCell::new(BTreeMap::new()),
+        });
+        let dbus_name = Some(self.dbus_name.as_str());
+        let added_state = state.clone();
+        let removed_state = state.clone();
+        let properties_state = state.clone();
+        let subscriptions = vec![
+            self.connection.signal_subscribe(dbus_name, Some(OBJECT_MANAGER_INTERFACE), Some("InterfacesAdded"), Some(&self.object_path), None, SIGNAL_FLAGS_NONE,
+                move |_, _, _, _, _, parameters| added_state.add_interfaces(&variant_string(&parameters.child_value(0)), parse_interfaces(&parameters.child_value(1)))),
+            self.connection.signal_subscribe(dbus_name, Some(OBJECT_MANAGER_INTERFACE), Some("InterfacesRemoved"), Some(&self.object_path), None, SIGNAL_FLAGS_NONE,
+                move |_, _, _, _, _, parameters| removed_state.remove_interfaces(&variant_string(&parameters.child_value(0)), &parse_strings(&parameters.child_value(1)))),
+            self.connection.signal_subscribe(dbus_name, Some("org.freedesktop.DBus.Properties"), Some("PropertiesChanged"), None, None, SIGNAL_FLAGS_NONE,
+                move |_, _, object_path, _, _, parameters| properties_state.update_properties(object_path, &parameters)),
+        ];
+
+        let message = Message::new_method_call(&self.dbus_name, &self.object_path, OBJECT_MANAGER_INTERFACE, "GetManagedObjects");
+        let reply = try!(self.connection.send_message_with_reply_sync(message, SEND_MESSAGE_FLAGS_NONE, 100));
+        let objects = reply.get_body().child_value(0);
+        for index in 0..objects.n_children() {
+            let entry = objects.child_value(index);
+            state.add_interfaces(&variant_string(&entry.child_value(0)), parse_interfaces(&entry.child_value(1)));
+        }
+
+        Ok(ObjectManagerClient {
+            connection: self.connection,
+            dbus_name: self.dbus_name,
+            state: state,
+            _subscriptions: subscriptions,
+        })
+    }
+
+    /// Connect the object added event.
+    /// The callback receives the object path and the interfaces of the new object. It is also called for the objects returned by `GetManagedObjects` when the client is built.
+    pub fn connect_object_added<F: Fn(&str, &ObjectInterfaces) + 'static>(mut self, callback: F) -> Self {
+        self.object_added_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Connect the object removed event, emitted when the last interface of an object is removed.
+    pub fn connect_object_removed<F: Fn(&str) + 'static>(mut self, callback: F) -> Self {
+        self.object_removed_callback = Some(Box::new(callback));
+        self
+    }
+}
+
+#[doc(hidden)]
+pub fn invalid_args_error(message: &str) -> Error {
+    let message = CString::new(message).unwrap();
+    unsafe { from_glib_full(g_error_new_literal(g_dbus_error_quark(), G_DBUS_ERROR_INVALID_ARGS as i32, message.as_ptr())) }
+}
+
+fn object_manager_interface() -> Result<InterfaceInfo, Error> {
+    InterfaceInfo::builder(OBJECT_MANAGER_INTERFACE)
+        .method(MethodInfo::builder("GetManagedObjects")
+            .out_arg("object_paths_interfaces_and_properties", "a{oa{sa{sv}}}"))
+        .signal(SignalInfo::builder("InterfacesAdded")
+            .arg("object_path", "o")
+            .arg("interfaces_and_properties", "a{sa{sv}}"))
+        .signal(SignalInfo::builder("InterfacesRemoved")
+            .arg("object_path", "o")
+            .arg("interfaces", "as"))
+        .build()
+}
+
+/// Create a `(a{oa{sa{sv}}})` variant.
+fn managed_objects_variant(objects: Vec<(String, InterfacesAndProperties)>) -> Variant {
+    unsafe {
+        let entries: Vec<_> = objects.into_iter()
+            .map(|(object_path, interfaces)| {
+                let object_path = CString::new(object_path).unwrap();
+                g_variant_new_dict_entry(g_variant_new_object_path(object_path.as_ptr()), interfaces_variant(interfaces))
+            })
+            .collect();
+        let children = [new_array("{oa{sa{sv}}}", &entries)];
+        Variant::new(g_variant_new_tuple(children.as_ptr(), children.len()))
+    }
+}
+
+/// Create a `a{sa{sv}}` variant.
+unsafe fn interfaces_variant(interfaces: InterfacesAndProperties) -> *mut GVariant {
+    let entries: Vec<_> = interfaces.into_iter()
+        .map(|(interface_name, properties)| {
+            let properties: Vec<_> = properties.into_iter()
+                .map(|(name, value)| {
+                    let name = CString::new(name).unwrap();
+                    g_variant_new_dict_entry(g_variant_new_string(name.as_ptr()), g_variant_new_variant(value.to_glib()))
+                })
+                .collect();
+            let interface_name = CString::new(interface_name).unwrap();
+            g_variant_new_dict_entry(g_variant_new_string(interface_name.as_ptr()), new_array("{sv}", &properties))
+        })
+        .collect();
+    new_array("{sa{sv}}", &entries)
+}
+
+unsafe fn new_array(entry_type: &str, entries: &[*mut GVariant]) -> *mut GVariant {
+    let entry_type = CString::new(entry_type).unwrap();
+    let entry_type = g_variant_type_new(entry_type.as_ptr());
+    let array = g_variant_new_array(entry_type, entries.as_ptr(), entries.len());
+    g_variant_type_free(entry_type);
+    array
+}
+
+/// Parse a `a{sa{sv}}` variant.
+fn parse_interfaces(variant: &Variant) -> ObjectInterfaces {
+    (0..variant.n_children())
+        .map(|index| {
+            let entry = variant.child_value(index);
+            (variant_string(&entry.child_value(0)), parse_properties(&entry.child_value(1)))
+        })
+        .collect()
+}
+
+/// Parse a `a{sv}` variant.
+fn parse_properties(variant: &Variant) -> HashMap<String, Variant> {
+    (0..variant.n_children())
+        .map(|index| {
+            let entry = variant.child_value(index);
+            let value = unsafe { g_variant_get_variant(entry.child_value(1).to_glib()) };
+            (variant_string(&entry.child_value(0)), Variant::new(value))
+        })
+        .collect()
+}
+
+/// Parse a `as` variant.
+fn parse_strings(variant: &Variant) -> Vec<String> {
+    (0..variant.n_children())
+        .map(|index| variant_string(&variant.child_value(index)))
+        .collect()
+}
+
+/// Get the value of a string or object path variant.
+fn variant_string(variant: &Variant) -> String {
+    unsafe { CStr::from_ptr(g_variant_get_string(variant.to_glib(), null_mut())).to_string_lossy().into_owned() }
+}
@@ -21,13 +21,17 @@
 
 //! Owning Bus Names — Simple API for owning bus names.
 
+use std::cell::Cell;
 use std::ffi::CString;
+use std::rc::Rc;
 
-use gio_sys::{GBusNameOwnerFlags, GBusType, GDBusConnection, g_bus_own_name, g_bus_unown_name, G_BUS_TYPE_STARTER, G_BUS_TYPE_NONE, G_BUS_TYPE_SYSTEM, G_BUS_TYPE_SESSION};
+use gio_sys::{GBusNameOwnerFlags, GBusType, GDBusConnection, g_bus_own_name, g_bus_own_name_on_connection, g_bus_unown_name, G_BUS_TYPE_STARTER, G_BUS_TYPE_NONE, G_BUS_TYPE_SYSTEM, G_BUS_TYPE_SESSION};
 use glib::translate::ToGlib;
 use libc::{c_char, c_void};
 
 use connection::Connection;
+#[cfg(feature = "futures")]
+use future::{EventSender, EventStream};
 
 bitflags! {
     /// Flags used in `OwnName::new()`.
@@ -38,13 +42,28 @@ bitflags! {
         const NAME_OWNER_FLAGS_ALLOW_REPLACEMENT = 1,
         /// If another message bus connection owns the name and have specified `G_BUS_NAME_OWNER_FLAGS_ALLOW_REPLACEMENT`, then take the name from the other connection.
         const NAME_OWNER_FLAGS_REPLACE = 2,
+        /// If another message bus connection owns the name, immediately return an error from `RequestName` rather than entering the waiting queue for that name.
+        const NAME_OWNER_FLAGS_DO_NOT_QUEUE = 4,
     }
 }
 
+enum Target {
+    Bus(Type),
+    Connection(Connection),
+}
+
+struct OwnNameCallbacks {
+    bus_acquired_callback: Option<Box<Fn(&Connection)>>,
+    name_acquired_callback: Option<Box<Fn(&Connection)>>,
+    name_lost_callback: Option<Box<Fn(Option<&Connection>)>>,
+    owner: Rc<Cell<bool>>,
+}
+
 /// Owning Bus Names.
-#[derive(Clone)]
+/// The name is released when this value is dropped, so wrap it in a `Rc` to share it.
 pub struct OwnName {
     id:  u32,
+    owner: Rc<Cell<bool>>,
 }
 
 impl OwnName {
@@ -59,19 +78,24 @@ impl OwnName {
     /// If you plan on exporting objects (using e.g. `g_dbus_connection_register_object()`), note that it is generally too late to export the objects in `name_acquired_handler`. Instead, you can do this in `bus_acquired_handler` since you are guaranteed that this will run before `name` is requested from the bus.
     /// This behavior makes it very simple to write applications that wants to own names and export objects. Simply register objects to be exported in `bus_acquired_handler` and unregister the objects (if any) in `name_lost_handler`.
     pub fn new(bus_type: Type, name: &str, flags: NameOwnerFlags) -> OwnNameBuilder {
-        OwnNameBuilder {
-            bus_acquired_callback: None,
-            bus_type: bus_type,
-            flags: flags,
-            name: name.to_string(),
-        }
+        OwnNameBuilder::new(Target::Bus(bus_type), name, flags)
     }
 
-    #[doc(hidden)]
-    pub fn from_id(id: u32) -> OwnName {
-        OwnName {
-            id: id,
-        }
+    /// Like `OwnName::new()` but takes a `Connection` instead of a `Type`.
+    /// Since the connection already exists, the bus acquired callback is never invoked.
+    pub fn new_on_connection(connection: &Connection, name: &str, flags: NameOwnerFlags) -> OwnNameBuilder {
+        OwnNameBuilder::new(Target::Connection(connection.clone()), name, flags)
+    }
+
+    /// Whether the name is currently owned by this process.
+    pub fn is_owner(&self) -> bool {
+        self.owner.get()
+    }
+
+    /// Stops owning the name. This is the same as dropping the `OwnName`.
+    /// The callbacks are freed once GLib no longer needs them, and no callbacks will be invoked after this call.
+    pub fn release(self) {
+        drop(self);
     }
 }
 
@@ -81,40 +105,121 @@ impl Drop for OwnName {
     }
 }
 
+/// An event of an owned name.
+#[cfg(feature = "futures")]
+pub enum NameEvent {
+    /// The name was acquired.
+    Acquired(Connection),
+    /// The name was lost. The connection is `None` if the connection to the bus could not be made.
+    Lost(Option<Connection>),
+}
+
+/// Stream of the events of an `OwnName`, created by `OwnNameBuilder::build_stream()`.
+#[cfg(feature = "futures")]
+pub type OwnNameStream = EventStream<NameEvent, OwnName>;
+
 /// `OwnName` builder
 pub struct OwnNameBuilder {
     bus_acquired_callback: Option<Box<Fn(&Connection)>>,
-    bus_type: Type,
     flags: NameOwnerFlags,
     name: String,
+    name_acquired_callback: Option<Box<Fn(&Connection)>>,
+    name_lost_callback: Option<Box<Fn(Option<&Connection>)>>,
+    target: Target,
 }
 
 impl OwnNameBuilder {
+    fn new(target: Target, name: &str, flags: NameOwnerFlags) -> Self {
+        OwnNameBuilder {
+            bus_acquired_callback: None,
+            flags: flags,
+            name: name.to_string(),
+            name_acquired_callback: None,
+            name_lost_callback: None,
+            target: target,
+        }
+    }
+
     /// Create the owning bus name.
     pub fn build(self) -> OwnName {
+        let name = CString::new(self.name).unwrap();
+        let flags = GBusNameOwnerFlags::from_bits_truncate(self.flags.bits());
+        let owner = Rc::new(Cell::new(false));
+        let callbacks = Box::new(OwnNameCallbacks {
+            bus_acquired_callback: self.bus_acquired_callback,
+            name_acquired_callback: self.name_acquired_callback,
+            name_lost_callback: self.name_lost_callback,
+            owner: owner.clone(),
+        });
+        let user_data = Box::into_raw(callbacks) as *mut _;
         let id =
-            unsafe {
-                let name = CString::new(self.name).unwrap();
-                let callback: Box<Box<Fn(&Connection) + 'static>> = Box::new(self.bus_acquired_callback.unwrap());
-                g_bus_own_name(self.bus_type.to_glib(), name.as_ptr(),
-                    GBusNameOwnerFlags::from_bits_truncate(self.flags.bits()), Some(bus_acquired_handler),
-                    Some(name_acquired_handler), Some(name_lost_handler),
-                    Box::into_raw(callback) as *mut _, None
-                )
+            match self.target {
+                Target::Bus(bus_type) => unsafe {
+                    g_bus_own_name(bus_type.to_glib(), name.as_ptr(), flags, Some(bus_acquired_handler),
+                        Some(name_acquired_handler), Some(name_lost_handler),
+                        user_data, Some(free_callbacks)
+                    )
+                },
+                Target::Connection(connection) => unsafe {
+                    g_bus_own_name_on_connection(connection.to_glib(), name.as_ptr(), flags,
+                        Some(name_acquired_handler), Some(name_lost_handler),
+                        user_data, Some(free_callbacks)
+                    )
+                },
             };
         OwnName {
             id: id,
+            owner: owner,
         }
     }
 
+    /// Start owning the name and return a stream of the name acquired and lost events.
+    /// The bus acquired callback should still be used to export objects, since it is called before the name is requested.
+    /// The callbacks connected on the builder are still called.
+    #[cfg(feature = "futures")]
+    pub fn build_stream(mut self) -> OwnNameStream {
+        let sender = EventSender::new();
+        let acquired_sender = sender.clone();
+        let acquired_callback = self.name_acquired_callback.take();
+        self.name_acquired_callback = Some(Box::new(move |connection| {
+            if let Some(ref callback) = acquired_callback {
+                callback(connection);
+            }
+            acquired_sender.send(NameEvent::Acquired(connection.clone()));
+        }));
+        let lost_sender = sender.clone();
+        let lost_callback = self.name_lost_callback.take();
+        self.name_lost_callback = Some(Box::new(move |connection| {
+            if let Some(ref callback) = lost_callback {
+                callback(connection);
+            }
+            lost_sender.send(NameEvent::Lost(connection.cloned()));
+        }));
+        sender.stream(self.build())
+    }
+
     /// Connect the bus acquired event.
     pub fn connect_bus_acquired<F: Fn(&Connection) + 'static>(mut self, callback: F) -> Self {
         self.bus_acquired_callback = Some(Box::new(callback));
         self
     }
+
+    /// Connect the name acquired event.
+    pub fn connect_name_acquired<F: Fn(&Connection) + 'static>(mut self, callback: F) -> Self {
+        self.name_acquired_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// Connect the name lost event.
+    /// The connection is `None` if the connection to the bus could not be made.
+    pub fn connect_name_lost<F: Fn(Option<&Connection>) + 'static>(mut self, callback: F) -> Self {
+        self.name_lost_callback = Some(Box::new(callback));
+        self
+    }
 }
 
 /// An enumeration for well-known message buses.
+#[derive(Clone, Copy)]
 pub enum Type {
     /// An alias for the message bus that activated the process, if any.
     Starter,
@@ -140,15 +245,34 @@ impl ToGlib for Type {
     }
 }
 
+unsafe extern "C" fn free_callbacks(user_data: *mut c_void) {
+    drop(Box::from_raw(user_data as *mut OwnNameCallbacks));
+}
+
 unsafe extern "C" fn bus_acquired_handler(connection: *mut GDBusConnection, _name: *const c_char, user_data: *mut c_void) {
-    let callback: &Box<Fn(&Connection) + 'static> = &*(user_data as *const Box<_>);
-    callback(&Connection::new(connection));
+    let callbacks = &*(user_data as *const OwnNameCallbacks);
+    if let Some(ref callback) = callbacks.bus_acquired_callback {
+        callback(&Connection::new(connection));
+    }
 }
 
-unsafe extern "C" fn name_acquired_handler(_connection: *mut GDBusConnection, _name: *const c_char, _user_data: *mut c_void) {
-    // TODO
+unsafe extern "C" fn name_acquired_handler(connection: *mut GDBusConnection, _name: *const c_char, user_data: *mut c_void) {
+    let callbacks = &*(user_data as *const OwnNameCallbacks);
+    callbacks.owner.set(true);
+    if let Some(ref callback) = callbacks.name_acquired_callback {
+        callback(&Connection::new(connection));
+    }
 }
 
-unsafe extern "C" fn name_lost_handler(_connection: *mut GDBusConnection, _name: *const c_char, _user_data: *mut c_void) {
-    // TODO
+unsafe extern "C" fn name_lost_handler(connection: *mut GDBusConnection, _name: *const c_char, user_data: *mut c_void) {
+    let callbacks = &*(user_data as *const OwnNameCallbacks);
+    callbacks.owner.set(false);
+    if let Some(ref callback) = callbacks.name_lost_callback {
+        if connection.is_null() {
+            callback(None);
+        }
+        else {
+            callback(Some(&Connection::new(connection)));
+        }
+    }
 }
@@ -0,0 +1,384 @@
+/*
+ * Copyright (c) 2016 Boucher, Antoni <bouanto@zoho.com>
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the "Software"), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ */
+
+//! `GDBusProxy` — Client-side D-Bus interface proxy.
+//!
+//! Unlike the proxies generated by `dbus_interface!` and `#[proxy]`, a `Proxy` calls methods by name, for interfaces only known at runtime.
+
+use std::ffi::{CStr, CString};
+use std::mem::transmute;
+use std::ptr::{null, null_mut};
+
+use gio_sys::{GAsyncResult, GDBusCallFlags, GDBusProxy, GDBusProxyFlags, g_dbus_proxy_call, g_dbus_proxy_call_finish, g_dbus_proxy_call_sync, g_dbus_proxy_get_cached_property, g_dbus_proxy_get_cached_property_names, g_dbus_proxy_get_connection, g_dbus_proxy_get_default_timeout, g_dbus_proxy_get_flags, g_dbus_proxy_get_interface_info, g_dbus_proxy_get_interface_name, g_dbus_proxy_get_name, g_dbus_proxy_get_name_owner, g_dbus_proxy_get_object_path, g_dbus_proxy_new_for_bus_sync, g_dbus_proxy_new_sync, g_dbus_proxy_set_cached_property, g_dbus_proxy_set_default_timeout, g_dbus_proxy_set_interface_info};
+use glib::error::Error;
+use glib::translate::{ToGlib, from_glib_full};
+use glib_sys::{GError, GVariant, g_free, g_strfreev};
+use gobject_sys::{GClosure, GObject, g_object_ref, g_object_unref, g_signal_connect_data, g_signal_handler_disconnect};
+use libc::{c_char, c_ulong, c_void};
+
+use connection::Connection;
+use future::{self, Reply};
+use node_info::InterfaceInfo;
+use own_name::Type;
+use variant::{ToVariant, Variant};
+
+bitflags! {
+    /// Flags used when constructing a `Proxy`.
+    pub flags ProxyFlags: u32 {
+        /// No flags set.
+        const PROXY_FLAGS_NONE = 0,
+        /// Don't load properties.
+        const PROXY_FLAGS_DO_NOT_LOAD_PROPERTIES = 1,
+        /// Don't connect to signals on the remote object.
+        const PROXY_FLAGS_DO_NOT_CONNECT_SIGNALS = 2,
+        /// If the proxy is for a well-known name, do not ask the bus to launch an owner during proxy initialization or a method call.
+        const PROXY_FLAGS_DO_NOT_AUTO_START = 4,
+        /// If set, the `g-properties-changed` signal is not emitted for properties whose value was invalidated, but the proxy gets the new value with `Get` instead.
+        const PROXY_FLAGS_GET_INVALIDATED_PROPERTIES = 8,
+        /// If the proxy is for a well-known name, do not ask the bus to launch an owner during proxy initialization, but allow it to be autostarted by a method call.
+        const PROXY_FLAGS_DO_NOT_AUTO_START_AT_CONSTRUCTION = 16,
+    }
+}
+
+bitflags! {
+    /// Flags used in `Proxy::call()`.
+    pub flags CallFlags: u32 {
+        /// No flags set.
+        const CALL_FLAGS_NONE = 0,
+        /// The bus must not launch an owner for the destination name in response to this method invocation.
+        const CALL_FLAGS_NO_AUTO_START = 1,
+        /// The caller is prepared to wait for interactive authorization.
+        const CALL_FLAGS_ALLOW_INTERACTIVE_AUTHORIZATION = 2,
+    }
+}
+
+type CallCallback = Box<Box<FnOnce(Result<Variant, Error>) + 'static>>;
+
+type PropertiesChangedCallback = Box<Fn(&Proxy, Variant, Vec<String>) + 'static>;
+
+type SignalCallback = Box<Fn(&Proxy, Option<&str>, &str, Variant) + 'static>;
+
+type NameOwnerCallback = Box<Fn(&Proxy, Option<String>) + 'static>;
+
+/// A proxy for a D-Bus interface on a remote object.
+/// When the proxy is created for a well-known name, it tracks the unique name of its owner: see `name_owner()`.
+pub struct Proxy(*mut GDBusProxy);
+
+impl Proxy {
+    /// Create a new proxy from a pointer, taking a new reference on it.
+    pub fn new(proxy: *mut GDBusProxy) -> Self {
+        Proxy(unsafe { g_object_ref(proxy as *mut _) as *mut _ })
+    }
+
+    /// Creates a proxy for accessing `interface_name` on the remote object at `object_path` owned by `name` at `connection` and synchronously loads D-Bus properties unless the `PROXY_FLAGS_DO_NOT_LOAD_PROPERTIES` flag is used.
+    /// If the `PROXY_FLAGS_DO_NOT_CONNECT_SIGNALS` flag is not set, also sets up match rules for signals.
+    /// `name` must be `None` if `connection` is not a message bus connection.
+    /// If `info` is given, it is used to check the method calls, the received signals and the cached properties.
+    pub fn new_sync(connection: &Connection, flags: ProxyFlags, info: Option<&InterfaceInfo>, name: Option<&str>, object_path: &str, interface_name: &str) -> Result<Self, Error> {
+        let name = name.map(|name| CString::new(name).unwrap());
+        let object_path = CString::new(object_path).unwrap();
+        let interface_name = CString::new(interface_name).unwrap();
+        let mut error = null_mut();
+        let proxy = unsafe { g_dbus_proxy_new_sync(connection.to_glib(), GDBusProxyFlags::from_bits_truncate(flags.bits()), info_ptr(info), name.as_ref().map_or(null(), |name| name.as_ptr()),
+            object_path.as_ptr(), interface_name.as_ptr(), null_mut(), &mut error) };
+        proxy_result(proxy, error)
+    }
+
+    /// Like `new_sync()` but takes a `Type` instead of a `Connection`.
+    pub fn new_for_bus_sync(bus_type: Type, flags: ProxyFlags, info: Option<&InterfaceInfo>, name: &str, object_path: &str, interface_name: &str) -> Result<Self, Error> {
+        let name = CString::new(name).unwrap();
+        let object_path = CString::new(object_path).unwrap();
+        let interface_name = CString::new(interface_name).unwrap();
+        let mut error = null_mut();
+        let proxy = unsafe { g_dbus_proxy_new_for_bus_sync(bus_type.to_glib(), GDBusProxyFlags::from_bits_truncate(flags.bits()), info_ptr(info), name.as_ptr(), object_path.as_ptr(),
+            interface_name.as_ptr(), null_mut(), &mut error) };
+        proxy_result(proxy, error)
+    }
+
+    /// Asynchronously invokes the `method_name` method on the proxy and calls `callback` with the reply, a tuple of the out arguments.
+    /// If `method_name` contains any dots, then the name is split into interface and method name parts. This allows using the proxy for invoking methods on other interfaces.
+    /// If the interface info of the proxy is set, `parameters` is checked against the in arguments of the method.
+    /// `timeout_msec` is the timeout in milliseconds, -1 to use the default timeout of the proxy or `i32::MAX` for no timeout.
+    pub fn call<T: ToVariant, F: FnOnce(Result<Variant, Error>) + 'static>(&self, method_name: &str, parameters: T, flags: CallFlags, timeout_msec: i32, callback: F) {
+        let method_name = CString::new(method_name).unwrap();
+        let callback: CallCallback = Box::new(Box::new(callback));
+        unsafe { g_dbus_proxy_call(self.0, method_name.as_ptr(), parameters.to_variant().to_glib(), GDBusCallFlags::from_bits_truncate(flags.bits()), timeout_msec, null_mut(),
+            Some(call_callback), Box::into_raw(callback) as *mut _) };
+    }
+
+    /// Like `call()` but returns a future resolving to the reply.
+    pub fn call_future<T: ToVariant>(&self, method_name: &str, parameters: T, flags: CallFlags, timeout_msec: i32) -> Reply<Variant> {
+        let (sender, reply) = future::channel();
+        self.call(method_name, parameters, flags, timeout_msec, move |result| sender.send(result));
+        reply
+    }
+
+    /// Synchronously invokes the `method_name` method on the proxy and returns the reply, a tuple of the out arguments.
+    /// See `call()` for the meaning of the parameters.
+    pub fn call_sync<T: ToVariant>(&self, method_name: &str, parameters: T, flags: CallFlags, timeout_msec: i32) -> Result<Variant, Error> {
+        let method_name = CString::new(method_name).unwrap();
+        let mut error = null_mut();
+        let result = unsafe { g_dbus_proxy_call_sync(self.0, method_name.as_ptr(), parameters.to_variant().to_glib(), GDBusCallFlags::from_bits_truncate(flags.bits()), timeout_msec, null_mut(), &mut error) };
+        variant_result(result, error)
+    }
+
+    /// Looks up the value for a property from the cache. This call does no blocking IO.
+    /// Returns `None` if the value is not in the cache, e.g. if the remote object has no such property or the properties were not loaded.
+    pub fn cached_property(&self, property_name: &str) -> Option<Variant> {
+        let property_name = CString::new(property_name).unwrap();
+        let value = unsafe { g_dbus_proxy_get_cached_property(self.0, property_name.as_ptr()) };
+        if value.is_null() {
+            None
+        }
+        else {
+            Some(Variant::new(value))
+        }
+    }
+
+    /// Gets the names of all cached properties on the proxy.
+    pub fn cached_property_names(&self) -> Vec<String> {
+        unsafe {
+            let names = g_dbus_proxy_get_cached_property_names(self.0);
+            let result = strv_to_vec(names as *const _);
+            g_strfreev(names);
+            result
+        }
+    }
+
+    /// Sets or, when `value` is `None`, invalidates the value of a cached property.
+    /// This does not set the property on the remote object, it only changes the local cache, e.g. for properties that are not emitting `PropertiesChanged`.
+    pub fn set_cached_property(&self, property_name: &str, value: Option<Variant>) {
+        let property_name = CString::new(property_name).unwrap();
+        unsafe { g_dbus_proxy_set_cached_property(self.0, property_name.as_ptr(), value.map_or(null_mut(), |value| value.to_glib())) };
+    }
+
+    /// Gets the connection the proxy is for.
+    pub fn connection(&self) -> Connection {
+        Connection::new(unsafe { g_dbus_proxy_get_connection(self.0) })
+    }
+
+    /// Gets the timeout, in milliseconds, used when -1 is passed as the timeout of `call()` and `call_sync()`.
+    pub fn default_timeout(&self) -> i32 {
+        unsafe { g_dbus_proxy_get_default_timeout(self.0) }
+    }
+
+    /// Sets the timeout, in milliseconds, used when -1 is passed as the timeout of `call()` and `call_sync()`.
+    pub fn set_default_timeout(&self, timeout_msec: i32) {
+        unsafe { g_dbus_proxy_set_default_timeout(self.0, timeout_msec) };
+    }
+
+    /// Gets the flags that the proxy was constructed with.
+    pub fn flags(&self) -> ProxyFlags {
+        ProxyFlags::from_bits_truncate(unsafe { g_dbus_proxy_get_flags(self.0) }.bits())
+    }
+
+    /// Gets the interface info used to check the calls, signals and properties of the proxy.
+    pub fn interface_info(&self) -> Option<InterfaceInfo> {
+        let info = unsafe { g_dbus_proxy_get_interface_info(self.0) };
+        if info.is_null() {
+            None
+        }
+        else {
+            Some(InterfaceInfo::new(info))
+        }
+    }
+
+    /// Sets the interface info used to check the calls, signals and properties of the proxy, or stops checking them when `info` is `None`.
+    pub fn set_interface_info(&self, info: Option<&InterfaceInfo>) {
+        unsafe { g_dbus_proxy_set_interface_info(self.0, info_ptr(info)) };
+    }
+
+    /// Gets the D-Bus interface name the proxy is for.
+    pub fn interface_name(&self) -> String {
+        unsafe { CStr::from_ptr(g_dbus_proxy_get_interface_name(self.0)).to_string_lossy().into_owned() }
+    }
+
+    /// Gets the name the proxy was constructed for, or `None` if the connection is not a message bus connection.
+    pub fn name(&self) -> Option<String> {
+        unsafe { optional_string(g_dbus_proxy_get_name(self.0)) }
+    }
+
+    /// The unique name that owns the name the proxy is for, or `None` if no-one currently owns that name.
+    /// Use `connect_name_owner_notify()` to be notified of the changes of owner.
+    pub fn name_owner(&self) -> Option<String> {
+        unsafe {
+            let name_owner = g_dbus_proxy_get_name_owner(self.0);
+            let result = optional_string(name_owner);
+            g_free(name_owner as *mut _);
+            result
+        }
+    }
+
+    /// Gets the object path the proxy is for.
+    pub fn object_path(&self) -> String {
+        unsafe { CStr::from_ptr(g_dbus_proxy_get_object_path(self.0)).to_string_lossy().into_owned() }
+    }
+
+    /// Connect the `g-properties-changed` signal, emitted when one or more D-Bus properties on the proxy changes.
+    /// The callback receives the changed properties, as a `a{sv}` variant, and the names of the invalidated properties.
+    /// The local cache has already been updated when the callback is called.
+    pub fn connect_properties_changed<F: Fn(&Proxy, Variant, Vec<String>) + 'static>(&self, callback: F) -> SignalHandler {
+        let callback: PropertiesChangedCallback = Box::new(callback);
+        self.connect("g-properties-changed", unsafe { transmute(properties_changed_callback as *const ()) }, Box::into_raw(Box::new(callback)) as *mut _,
+            free_properties_changed_callback)
+    }
+
+    /// Connect the `g-signal` signal, emitted when a signal from the remote object and interface that the proxy is for, has been received.
+    /// The callback receives the sender, the signal name and the parameters of the signal.
+    pub fn connect_signal<F: Fn(&Proxy, Option<&str>, &str, Variant) + 'static>(&self, callback: F) -> SignalHandler {
+        let callback: SignalCallback = Box::new(callback);
+        self.connect("g-signal", unsafe { transmute(signal_callback as *const ()) }, Box::into_raw(Box::new(callback)) as *mut _, free_signal_callback)
+    }
+
+    /// Connect the notification of the changes of the name owner.
+    /// The callback receives the new owner, or `None` if the name has no owner anymore.
+    pub fn connect_name_owner_notify<F: Fn(&Proxy, Option<String>) + 'static>(&self, callback: F) -> SignalHandler {
+        let callback: NameOwnerCallback = Box::new(callback);
+        self.connect("notify::g-name-owner", unsafe { transmute(name_owner_callback as *const ()) }, Box::into_raw(Box::new(callback)) as *mut _, free_name_owner_callback)
+    }
+
+    fn connect(&self, signal: &str, handler: unsafe extern "C" fn(), user_data: *mut c_void, destroy: unsafe extern "C" fn(*mut c_void, *mut GClosure)) -> SignalHandler {
+        let signal = CString::new(signal).unwrap();
+        let id = unsafe { g_signal_connect_data(self.0 as *mut _, signal.as_ptr(), Some(handler), user_data, Some(destroy), 0) };
+        SignalHandler {
+            id: id,
+            proxy: self.clone(),
+        }
+    }
+
+    /// Convert to the sys type.
+    pub fn to_glib(&self) -> *mut GDBusProxy {
+        self.0
+    }
+}
+
+impl Clone for Proxy {
+    fn clone(&self) -> Self {
+        Proxy::new(self.0)
+    }
+}
+
+impl Drop for Proxy {
+    fn drop(&mut self) {
+        unsafe { g_object_unref(self.0 as *mut _) };
+    }
+}
+
+/// A callback connected on a `Proxy`.
+/// The callback is disconnected when this value is dropped.
+pub struct SignalHandler {
+    id: c_ulong,
+    proxy: Proxy,
+}
+
+impl Drop for SignalHandler {
+    fn drop(&mut self) {
+        unsafe { g_signal_handler_disconnect(self.proxy.0 as *mut _, self.id) };
+    }
+}
+
+fn info_ptr(info: Option<&InterfaceInfo>) -> *mut ::gio_sys::GDBusInterfaceInfo {
+    info.map_or(null_mut(), |info| info.to_glib())
+}
+
+fn proxy_result(proxy: *mut GDBusProxy, error: *mut GError) -> Result<Proxy, Error> {
+    if error.is_null() {
+        Ok(Proxy(proxy))
+    }
+    else {
+        Err(unsafe { from_glib_full(error) })
+    }
+}
+
+fn variant_result(variant: *mut GVariant, error: *mut GError) -> Result<Variant, Error> {
+    if error.is_null() {
+        Ok(Variant::new(variant))
+    }
+    else {
+        Err(unsafe { from_glib_full(error) })
+    }
+}
+
+unsafe fn optional_string(string: *const c_char) -> Option<String> {
+    if string.is_null() {
+        None
+    }
+    else {
+        Some(CStr::from_ptr(string).to_string_lossy().into_owned())
+    }
+}
+
+unsafe fn strv_to_vec(mut strv: *const *const c_char) -> Vec<String> {
+    let mut result = vec![];
+    if !strv.is_null() {
+        while !(*strv).is_null() {
+            result.push(CStr::from_ptr(*strv).to_string_lossy().into_owned());
+            strv = strv.offset(1);
+        }
+    }
+    result
+}
+
+unsafe extern "C" fn call_callback(source_object: *mut GObject, res: *mut GAsyncResult, user_data: *mut c_void) {
+    let mut error = null_mut();
+    let result = g_dbus_proxy_call_finish(source_object as *mut _, res, &mut error);
+    // The callback is only invoked once, so it can be freed here.
+    let callback: CallCallback = Box::from_raw(user_data as *mut Box<_>);
+    callback(variant_result(result, error));
+}
+
+unsafe extern "C" fn properties_changed_callback(proxy: *mut GDBusProxy, changed_properties: *mut GVariant, invalidated_properties: *const *const c_char, user_data: *mut c_void) {
+    let callback = &*(user_data as *const PropertiesChangedCallback);
+    let proxy = Proxy::new(proxy);
+    callback(&proxy, Variant::new(changed_properties), strv_to_vec(invalidated_properties));
+}
+
+unsafe extern "C" fn signal_callback(proxy: *mut GDBusProxy, sender_name: *const c_char, signal_name: *const c_char, parameters: *mut GVariant, user_data: *mut c_void) {
+    let callback = &*(user_data as *const SignalCallback);
+    let proxy = Proxy::new(proxy);
+    let sender_name =
+        if sender_name.is_null() {
+            None
+        }
+        else {
+            Some(CStr::from_ptr(sender_name).to_str().unwrap())
+        };
+    callback(&proxy, sender_name, CStr::from_ptr(signal_name).to_str().unwrap(), Variant::new(parameters));
+}
+
+unsafe extern "C" fn name_owner_callback(proxy: *mut GDBusProxy, _param_spec: *mut c_void, user_data: *mut c_void) {
+    let callback = &*(user_data as *const NameOwnerCallback);
+    let proxy = Proxy::new(proxy);
+    let name_owner = proxy.name_owner();
+    callback(&proxy, name_owner);
+}
+
+unsafe extern "C" fn free_properties_changed_callback(user_data: *mut c_void, _closure: *mut GClosure) {
+    drop(Box::from_raw(user_data as *mut PropertiesChangedCallback));
+}
+
+unsafe extern "C" fn free_signal_callback(user_data: *mut c_void, _closure: *mut GClosure) {
+    drop(Box::from_raw(user_data as *mut SignalCallback));
+}
+
+unsafe extern "C" fn free_name_owner_callback(user_data: *mut c_void, _closure: *mut GClosure) {
+    drop(Box::from_raw(user_data as *mut NameOwnerCallback));
+}
@@ -23,9 +23,10 @@
 
 use std::ffi::{CStr, CString};
 use std::mem::zeroed;
+use std::ptr::{null, null_mut};
 
-use glib_sys::{GVariant, g_variant_get, g_variant_new};
-use libc::{c_char, c_int, int16_t, int32_t, int64_t, uint16_t, uint32_t, uint64_t, uint8_t};
+use glib_sys::{GFALSE, GVariant, g_variant_equal, g_variant_get, g_variant_get_child_value, g_variant_get_string, g_variant_lookup_value, g_variant_n_children, g_variant_new, g_variant_new_array, g_variant_ref, g_variant_type_free, g_variant_type_new, g_variant_unref};
+use libc::{c_char, c_double, c_int, int16_t, int32_t, int64_t, uint16_t, uint32_t, uint64_t, uint8_t};
 
 /// Wrapper for boolean c type.
 pub struct CBool(c_int);
@@ -70,6 +71,51 @@ macro_rules! numeric_variant {
     };
 }
 
+/// Conversions of a wrapper of `String` to and from a string `Variant` of type `$format`.
+macro_rules! string_variant {
+    ($rust_type:ident, $format:tt) => {
+        impl FromFFI for $rust_type {
+            type Input = *mut GVariant;
+
+            unsafe fn from_ffi(input: Self::Input) -> Self {
+                $rust_type(String::from_ffi(input))
+            }
+        }
+
+        impl FromFormat for $rust_type {
+            fn from_format() -> &'static str {
+                concat!("@", $format)
+            }
+        }
+
+        impl ToFFI for $rust_type {
+            type Output = CString;
+
+            fn to_ffi(&self) -> Self::Output {
+                CString::new(self.0.as_bytes()).unwrap()
+            }
+        }
+
+        impl ToFormat for $rust_type {
+            fn to_format() -> &'static str {
+                $format
+            }
+        }
+    };
+}
+
+/// A D-Bus object path, e.g. `/org/freedesktop/DBus`, which is sent with the type `o` rather than `s`.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ObjectPath(pub String);
+
+string_variant!(ObjectPath, "o");
+
+/// A D-Bus type signature, e.g. `a{sv}`, which is sent with the type `g` rather than `s`.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Signature(pub String);
+
+string_variant!(Signature, "g");
+
 /// Strongly typed value datatype.
 pub struct Variant(*mut GVariant);
 
@@ -79,12 +125,99 @@ impl Variant {
         Variant(variant)
     }
 
+    /// Create a new `Variant` holding a single basic value (e.g. of type `x` rather than `(x)`).
+    pub fn from_value<T: ToFFI + ToFormat>(value: &T) -> Self {
+        let ffi = value.to_ffi();
+        let format = CString::new(T::to_format()).unwrap();
+        Variant(unsafe { g_variant_new(format.as_ptr(), ffi.to_arg()) })
+    }
+
+    /// Reads a child item out of a container `Variant`.
+    pub fn child_value(&self, index: usize) -> Variant {
+        Variant(unsafe { g_variant_get_child_value(self.0, index) })
+    }
+
+    /// Get the number of children of a container `Variant`.
+    pub fn n_children(&self) -> usize {
+        unsafe { g_variant_n_children(self.0) }
+    }
+
+    /// Looks up a value in a dictionary `Variant`.
+    pub fn lookup_value(&self, key: &str) -> Option<Variant> {
+        let key = CString::new(key).unwrap();
+        let value = unsafe { g_variant_lookup_value(self.0, key.as_ptr(), null()) };
+        if value.is_null() {
+            None
+        }
+        else {
+            Some(Variant(value))
+        }
+    }
+
+    /// Get the single basic value held by the variant.
+    pub fn get_value<T: FromFFI + FromFormat>(&self) -> T {
+        let mut ffi: <T as FromFFI>::Input = unsafe { zeroed() };
+        let format = CString::new(T::from_format()).unwrap();
+        unsafe {
+            g_variant_get(self.0, format.as_ptr(), &mut ffi);
+            T::from_ffi(ffi)
+        }
+    }
+
     /// Convert the variant to the sys type.
     pub fn to_glib(&self) -> *mut GVariant {
         self.0
     }
 }
 
+impl Clone for Variant {
+    fn clone(&self) -> Self {
+        Variant(unsafe { g_variant_ref(self.0) })
+    }
+}
+
+impl PartialEq for Variant {
+    fn eq(&self, other: &Variant) -> bool {
+        unsafe { g_variant_equal(self.0 as *const _, other.0 as *const _) != GFALSE }
+    }
+}
+
+impl FromFFI for Variant {
+    type Input = *mut GVariant;
+
+    unsafe fn from_ffi(input: Self::Input) -> Self {
+        Variant(input)
+    }
+}
+
+impl FromFormat for Variant {
+    fn from_format() -> &'static str {
+        "v"
+    }
+}
+
+impl ToArg for *mut GVariant {
+    type Output = *mut GVariant;
+
+    fn to_arg(&self) -> Self::Output {
+        *self
+    }
+}
+
+impl ToFFI for Variant {
+    type Output = *mut GVariant;
+
+    fn to_ffi(&self) -> Self::Output {
+        self.0
+    }
+}
+
+impl ToFormat for Variant {
+    fn to_format() -> &'static str {
+        "v"
+    }
+}
+
 impl FromFFI for bool {
     type Input = c_int;
 
@@ -128,6 +261,67 @@ numeric_variant!(i32, int32_t, "i");
 numeric_variant!(u32, uint32_t, "u");
 numeric_variant!(i64, int64_t, "x");
 numeric_variant!(u64, uint64_t, "t");
+numeric_variant!(f64, c_double, "d");
+
+/// Conversions of `Vec<$rust_type>` from an array `Variant` matching `$from_format` and to one of type `$to_format`.
+macro_rules! array_variant {
+    ($rust_type:ty, $from_format:expr, $to_format:expr) => {
+        impl FromFFI for Vec<$rust_type> {
+            type Input = *mut GVariant;
+
+            unsafe fn from_ffi(input: Self::Input) -> Self {
+                let array = Variant(input);
+                let values = (0..array.n_children())
+                    .map(|index| array.child_value(index).get_value())
+                    .collect();
+                g_variant_unref(input);
+                values
+            }
+        }
+
+        impl FromFormat for Vec<$rust_type> {
+            fn from_format() -> &'static str {
+                $from_format
+            }
+        }
+
+        impl ToFFI for Vec<$rust_type> {
+            type Output = *mut GVariant;
+
+            fn to_ffi(&self) -> Self::Output {
+                let children: Vec<_> = self.iter().map(|value| Variant::from_value(value).to_glib()).collect();
+                let element_type = CString::new(&$to_format[2..]).unwrap();
+                unsafe {
+                    let element_type = g_variant_type_new(element_type.as_ptr());
+                    let array = g_variant_new_array(element_type, children.as_ptr(), children.len());
+                    g_variant_type_free(element_type);
+                    array
+                }
+            }
+        }
+
+        impl ToFormat for Vec<$rust_type> {
+            fn to_format() -> &'static str {
+                $to_format
+            }
+        }
+    };
+}
+
+array_variant!(bool, "@ab", "@ab");
+array_variant!(u8, "@ay", "@ay");
+array_variant!(i16, "@an", "@an");
+array_variant!(u16, "@aq", "@aq");
+array_variant!(i32, "@ai", "@ai");
+array_variant!(u32, "@au", "@au");
+array_variant!(i64, "@ax", "@ax");
+array_variant!(u64, "@at", "@at");
+array_variant!(f64, "@ad", "@ad");
+// Arrays of object paths and signatures can be read as well.
+array_variant!(String, "@a*", "@as");
+array_variant!(ObjectPath, "@ao", "@ao");
+array_variant!(Signature, "@ag", "@ag");
+array_variant!(Variant, "@av", "@av");
 
 /// Trait for converting to a value from its ffi representation.
 pub trait FromFFI {
@@ -156,11 +350,12 @@ impl<'a> FromFFI for &'a str {
 }
 
 impl FromFFI for String {
-    type Input = *mut c_char;
+    type Input = *mut GVariant;
 
     unsafe fn from_ffi(input: Self::Input) -> Self {
-        let result = CStr::from_ptr(input);
-        result.to_str().unwrap().to_string()
+        let result = CStr::from_ptr(g_variant_get_string(input, null_mut())).to_str().unwrap().to_string();
+        g_variant_unref(input);
+        result
     }
 }
 
@@ -182,9 +377,10 @@ impl<'a> FromFormat for &'a str {
     }
 }
 
+/// Any string type is accepted, so that object paths and signatures can be read as a `String`.
 impl FromFormat for String {
     fn from_format() -> &'static str {
-        "&s"
+        "*"
     }
 }
 
@@ -194,6 +390,11 @@ pub trait FromVariant: Sized {
     fn from_variant(variant: &Variant) -> Self;
 }
 
+impl FromVariant for () {
+    fn from_variant(_variant: &Variant) -> Self {
+    }
+}
+
 impl<P: FromFFI + FromFormat> FromVariant for (P,) {
     fn from_variant(variant: &Variant) -> Self {
         let mut ffi: <P as FromFFI>::Input = unsafe { zeroed() };
@@ -384,6 +585,20 @@ pub trait ToVariant {
     fn to_variant(&self) -> Variant;
 }
 
+impl ToVariant for () {
+    fn to_variant(&self) -> Variant {
+        let format = CString::new("()").unwrap();
+        Variant(unsafe { g_variant_new(format.as_ptr()) })
+    }
+}
+
+/// The variant is used as is, so it must be a tuple to be used as parameters or return value.
+impl ToVariant for Variant {
+    fn to_variant(&self) -> Variant {
+        Variant(self.0)
+    }
+}
+
 impl<P: ToFFI + ToFormat> ToVariant for (P,) {
     fn to_variant(&self) -> Variant {
         let ffi = self.0.to_ffi();
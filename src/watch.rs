@@ -21,13 +21,17 @@
 
 //! Watching Bus Names — Simple API for watching bus names.
 
-use std::ffi::CString;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::rc::Rc;
 
-use gio_sys::{GBusNameWatcherFlags, GDBusConnection, g_bus_unwatch_name, g_bus_watch_name};
+use gio_sys::{GBusNameWatcherFlags, GDBusConnection, g_bus_unwatch_name, g_bus_watch_name, g_bus_watch_name_on_connection};
 use glib::translate::ToGlib;
 use libc::{c_char, c_void};
 
 use connection::Connection;
+#[cfg(feature = "futures")]
+use future::{EventSender, EventStream};
 use own_name::Type;
 
 bitflags! {
@@ -40,9 +44,34 @@ bitflags! {
     }
 }
 
+enum Target {
+    Bus(Type),
+    Connection(Connection),
+}
+
+/// An event of a watched name.
+#[cfg(feature = "futures")]
+pub enum WatchEvent {
+    /// The name has an owner, with the given unique name.
+    NameAppeared(Connection, String),
+    /// The name lost its owner. The connection is `None` if the connection to the bus could not be made.
+    NameVanished(Option<Connection>),
+}
+
+/// Stream of the events of a `Watch`, created by `WatchBuilder::build_stream()`.
+#[cfg(feature = "futures")]
+pub type WatchStream = EventStream<WatchEvent, Watch>;
+
+struct WatchCallbacks {
+    name_appeared_callback: Option<Box<Fn(&Connection, &str)>>,
+    name_vanished_callback: Option<Box<Fn(Option<&Connection>)>>,
+    owner: Rc<RefCell<Option<String>>>,
+}
+
 /// Watching Bus Names.
 pub struct Watch {
     id: u32,
+    owner: Rc<RefCell<Option<String>>>,
 }
 
 impl Watch {
@@ -52,12 +81,17 @@ impl Watch {
     /// Another guarantee is that invocations of `name_appeared_handler` and `name_vanished_handler` are guaranteed to alternate; that is, if `name_appeared_handler` is invoked then you are guaranteed that the next time one of the handlers is invoked, it will be `name_vanished_handler` . The reverse is also true.
     /// This behavior makes it very simple to write applications that want to take action when a certain name exists. Basically, the application should create object proxies in `name_appeared_handler` and destroy them again (if any) in `name_vanished_handler`.
     pub fn name(bus_type: Type, name: &str, flags: NameWatcherFlags) -> WatchBuilder {
-        WatchBuilder {
-            bus_type: bus_type,
-            name: name.to_string(),
-            name_appeared_callback: None,
-            flags: flags,
-        }
+        WatchBuilder::new(Target::Bus(bus_type), name, flags)
+    }
+
+    /// Like `Watch::name()` but takes a `Connection` instead of a `Type`.
+    pub fn name_on_connection(connection: &Connection, name: &str, flags: NameWatcherFlags) -> WatchBuilder {
+        WatchBuilder::new(Target::Connection(connection.clone()), name, flags)
+    }
+
+    /// The unique name of the current owner of the watched name, or `None` if the name currently has no owner.
+    pub fn current_owner(&self) -> Option<String> {
+        self.owner.borrow().clone()
     }
 }
 
@@ -69,38 +103,110 @@ impl Drop for Watch {
 
 /// `Watch` builder.
 pub struct WatchBuilder {
-    bus_type: Type,
+    target: Target,
     name: String,
     name_appeared_callback: Option<Box<Fn(&Connection, &str)>>,
+    name_vanished_callback: Option<Box<Fn(Option<&Connection>)>>,
     flags: NameWatcherFlags,
 }
 
 impl WatchBuilder {
+    fn new(target: Target, name: &str, flags: NameWatcherFlags) -> Self {
+        WatchBuilder {
+            target: target,
+            name: name.to_string(),
+            name_appeared_callback: None,
+            name_vanished_callback: None,
+            flags: flags,
+        }
+    }
+
     /// Create the watcher.
     pub fn build(self) -> Watch {
         let name = CString::new(self.name).unwrap();
-        let callback: Box<Box<Fn(&Connection, &str) + 'static>> = Box::new(self.name_appeared_callback.unwrap());
-        let id = unsafe { g_bus_watch_name(self.bus_type.to_glib(), name.as_ptr(), GBusNameWatcherFlags::from_bits_truncate(self.flags.bits()), Some(name_appeared_handler), Some(name_vanished_handler),
-            Box::into_raw(callback) as *mut _, None
-        )};
+        let owner = Rc::new(RefCell::new(None));
+        let callbacks = Box::new(WatchCallbacks {
+            name_appeared_callback: self.name_appeared_callback,
+            name_vanished_callback: self.name_vanished_callback,
+            owner: owner.clone(),
+        });
+        let flags = GBusNameWatcherFlags::from_bits_truncate(self.flags.bits());
+        let user_data = Box::into_raw(callbacks) as *mut _;
+        let id = match self.target {
+            Target::Bus(bus_type) => unsafe { g_bus_watch_name(bus_type.to_glib(), name.as_ptr(), flags, Some(name_appeared_handler), Some(name_vanished_handler),
+                user_data, Some(free_callbacks)
+            )},
+            Target::Connection(connection) => unsafe { g_bus_watch_name_on_connection(connection.to_glib(), name.as_ptr(), flags, Some(name_appeared_handler), Some(name_vanished_handler),
+                user_data, Some(free_callbacks)
+            )},
+        };
         Watch {
             id: id,
+            owner: owner,
         }
     }
 
+    /// Create the watcher and return a stream of its events.
+    /// The callbacks connected on the builder are still called.
+    #[cfg(feature = "futures")]
+    pub fn build_stream(mut self) -> WatchStream {
+        let sender = EventSender::new();
+        let appeared_sender = sender.clone();
+        let appeared_callback = self.name_appeared_callback.take();
+        self.name_appeared_callback = Some(Box::new(move |connection, name_owner| {
+            if let Some(ref callback) = appeared_callback {
+                callback(connection, name_owner);
+            }
+            appeared_sender.send(WatchEvent::NameAppeared(connection.clone(), name_owner.to_string()));
+        }));
+        let vanished_sender = sender.clone();
+        let vanished_callback = self.name_vanished_callback.take();
+        self.name_vanished_callback = Some(Box::new(move |connection| {
+            if let Some(ref callback) = vanished_callback {
+                callback(connection);
+            }
+            vanished_sender.send(WatchEvent::NameVanished(connection.cloned()));
+        }));
+        sender.stream(self.build())
+    }
+
     /// Connect the name appeared event.
+    /// The callback receives the unique name of the new owner.
     pub fn connect_name_appeared<F: Fn(&Connection, &str) + 'static>(mut self, callback: F) -> Self {
         self.name_appeared_callback = Some(Box::new(callback));
         self
     }
+
+    /// Connect the name vanished event.
+    /// The connection is `None` if the connection to the bus could not be made.
+    pub fn connect_name_vanished<F: Fn(Option<&Connection>) + 'static>(mut self, callback: F) -> Self {
+        self.name_vanished_callback = Some(Box::new(callback));
+        self
+    }
+}
+
+unsafe extern "C" fn free_callbacks(user_data: *mut c_void) {
+    drop(Box::from_raw(user_data as *mut WatchCallbacks));
 }
 
 unsafe extern "C" fn name_appeared_handler(connection: *mut GDBusConnection, _name: *const c_char, name_owner: *const c_char, user_data: *mut c_void) {
-    let name_owner = CString::from_raw(name_owner as *mut _);
-    let callback: &Box<Fn(&Connection, &str) + 'static> = &*(user_data as *const Box<_>);
-    callback(&Connection::new(connection), &name_owner.into_string().unwrap());
+    let callbacks = &*(user_data as *const WatchCallbacks);
+    let name_owner = CStr::from_ptr(name_owner).to_str().unwrap();
+    *callbacks.owner.borrow_mut() = Some(name_owner.to_string());
+    if let Some(ref callback) = callbacks.name_appeared_callback {
+        callback(&Connection::new(connection), name_owner);
+    }
 }
 
-unsafe extern "C" fn name_vanished_handler(_connection: *mut GDBusConnection, _name: *const c_char, _user_data: *mut c_void) {
-    // TODO
+unsafe extern "C" fn name_vanished_handler(connection: *mut GDBusConnection, _name: *const c_char, user_data: *mut c_void) {
+    let callbacks = &*(user_data as *const WatchCallbacks);
+    *callbacks.owner.borrow_mut() = None;
+    if let Some(ref callback) = callbacks.name_vanished_callback {
+        if connection.is_null() {
+            callback(None);
+        }
+        else {
+            callback(Some(&Connection::new(connection)));
+        }
+    }
 }